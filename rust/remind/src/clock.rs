@@ -0,0 +1,25 @@
+use std::{io, time::Duration};
+
+use chrono::{DateTime, Local};
+
+/// Where waits get the time and sleep from, so they can be tested without waiting.
+pub trait Clock {
+    /// Current wall clock time.
+    fn now(&self) -> DateTime<Local>;
+
+    /// Sleeps for `duration`, returning how long it was paused for on top of it.
+    fn sleep(&self, duration: Duration) -> io::Result<Duration>;
+}
+
+/// The system clock, sleeping until paused or cancelled like `remind::sleep`.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Local> {
+        Local::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> io::Result<Duration> {
+        crate::pausable_sleep(duration)
+    }
+}
@@ -0,0 +1,72 @@
+use std::{
+    fs,
+    io::{self, ErrorKind},
+    path::PathBuf,
+};
+
+use remind::Delta;
+use serde::{de, Deserialize, Deserializer};
+
+use crate::Args;
+
+/// Defaults for the command line flags, read from the config file.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub once: bool,
+    #[serde(deserialize_with = "delta")]
+    pub repeat: Option<Delta>,
+    pub sound: Option<PathBuf>,
+    pub message: Option<String>,
+}
+
+fn delta<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Delta>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|s| Delta::parse(&s).map_err(de::Error::custom))
+        .transpose()
+}
+
+/// Location of the config file, ex: ~/.config/glutools/remind.toml
+fn path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("glutools").join("remind.toml"))
+}
+
+impl Config {
+    /// Reads the config file, a missing file meaning the built-in defaults are used.
+    pub fn load() -> io::Result<Self> {
+        let Some(path) = path() else {
+            return Ok(Self::default());
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(contents) => toml::from_str(&contents).map_err(|e| {
+                io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Invalid config {}: {}", path.display(), e),
+                )
+            }),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Fills in the flags that weren't given on the command line.
+    pub fn apply(&self, mut args: Args) -> Args {
+        // Flags for repeating win over the configured once, like they'd conflict with --once.
+        let repeats = args.count.is_some()
+            || args.every.is_some()
+            || args.until_ack
+            || args.repeat.is_some()
+            || args.repeat_until.is_some()
+            || args.repeat_backoff.is_some()
+            || args.jitter_repeat
+            || args.snooze.is_some();
+        args.once |= self.once && !repeats;
+        if !args.once {
+            args.repeat = args.repeat.or(self.repeat.clone());
+        }
+        args.sound = args.sound.or(self.sound.clone());
+
+        args
+    }
+}
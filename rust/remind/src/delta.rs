@@ -0,0 +1,677 @@
+use std::{error::Error, fmt::Display, str::FromStr};
+
+use chrono::{DateTime, Local, NaiveTime, TimeDelta};
+use serde::Serialize;
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Delta {
+    pub days: u32,
+    pub hours: u32,
+    pub minutes: u32,
+    pub seconds: u32,
+}
+
+impl Delta {
+    /// Splits a number of seconds into days, hours, minutes and seconds.
+    pub fn from_seconds(seconds: u64) -> Self {
+        Self {
+            days: u32::try_from(seconds / 86400).unwrap_or(u32::MAX),
+            hours: (seconds % 86400 / 3600) as u32,
+            minutes: (seconds % 3600 / 60) as u32,
+            seconds: (seconds % 60) as u32,
+        }
+    }
+
+    /// Length of the delta in seconds, used for every sleep and trigger time computation.
+    ///
+    /// Every field is a u32, so even u32::MAX days can't overflow a u64.
+    pub fn total_seconds(&self) -> u64 {
+        u64::from(self.days) * 86400
+            + u64::from(self.hours) * 3600
+            + u64::from(self.minutes) * 60
+            + u64::from(self.seconds)
+    }
+
+    /// Time the delta ends at when counted from `start`, failing instead of overflowing the calendar.
+    pub fn after(&self, start: DateTime<Local>) -> Result<DateTime<Local>, DeltaError> {
+        i64::try_from(self.total_seconds())
+            .ok()
+            .and_then(TimeDelta::try_seconds)
+            .and_then(|delta| start.checked_add_signed(delta))
+            .ok_or_else(|| DeltaError::new(DeltaErrorKind::Overflow, "Duration too large"))
+    }
+
+    /// Time left until the next occurrence of a clock time, rolling over to tomorrow if needed.
+    pub fn until(time: NaiveTime) -> Self {
+        let now = Local::now().naive_local();
+        let mut target = now.date().and_time(time);
+        if target <= now {
+            target += TimeDelta::days(1);
+        }
+
+        // Round up, so the reminder never triggers before the clock shows the time.
+        let millis = (target - now).num_milliseconds().unsigned_abs();
+        Self::from_seconds(millis.div_ceil(1000))
+    }
+
+    /// Parses the compact form like 1d2h30m15s, or ISO-8601 like PT2H30M.
+    pub fn parse(s: &str) -> Result<Self, DeltaError> {
+        // A zero delta has to be asked for explicitly, ex: 0s.
+        if s.trim().is_empty() {
+            return Err(DeltaError::new(
+                DeltaErrorKind::Empty,
+                "Empty duration, use 0s to remind right away",
+            ));
+        }
+        if s.trim_start().starts_with(['P', 'p']) {
+            return Self::from_iso8601(s);
+        }
+        if let Some(minutes) = s.trim().strip_prefix('+') {
+            return Self::from_minutes(minutes);
+        }
+
+        let mut delta = Self::default();
+        let mut number = String::new();
+        let mut seen: Vec<u64> = Vec::new();
+
+        // Units are case insensitive and whitespace is ignored, so "2H 30M" is 2h30m.
+        for c in s.chars().filter(|c| !c.is_whitespace()) {
+            let c = c.to_lowercase().next().unwrap_or(c);
+            let (unit, name, kind) = match c {
+                'w' => (604800, "weeks", DeltaErrorKind::InvalidWeeks),
+                'd' => (86400, "days", DeltaErrorKind::InvalidDays),
+                'h' => (3600, "hours", DeltaErrorKind::InvalidHours),
+                'm' => (60, "minutes", DeltaErrorKind::InvalidMinutes),
+                's' => (1, "seconds", DeltaErrorKind::InvalidSeconds),
+                // A comma separates units like "2h,30m", unless it's a decimal comma like "1,5h".
+                ',' if number.is_empty() => continue,
+                '.' | ',' => {
+                    if number.contains('.') {
+                        return Err(DeltaError::new(
+                            DeltaErrorKind::InvalidNumber,
+                            "Invalid number, too many decimal points",
+                        ));
+                    }
+                    number.push('.');
+                    continue;
+                }
+                _ => {
+                    if c.is_numeric() {
+                        number = format!("{}{}", number, c);
+                        continue;
+                    } else {
+                        return Err(DeltaError::new(
+                            DeltaErrorKind::InvalidSyntax,
+                            "Invalid syntax, ex: 1d2h30m15s",
+                        ));
+                    }
+                }
+            };
+
+            check_order(&mut seen, unit, &c.to_string())?;
+            let value = number
+                .parse()
+                .map_err(|_| DeltaError::new(kind, &format!("Invalid {}", name)))?;
+            delta.add(value, unit)?;
+            number.clear();
+        }
+        // A bare number would otherwise be dropped, so 30 would remind right away.
+        if !number.is_empty() {
+            return Err(DeltaError::new(
+                DeltaErrorKind::MissingUnit,
+                &format!("Missing unit after {}, ex: {}m", number, number),
+            ));
+        }
+
+        Ok(delta)
+    }
+
+    /// Parses plain English like "in 2 hours and 30 minutes", with the numbers written as digits.
+    pub fn from_natural(s: &str) -> Result<Self, DeltaError> {
+        let mut delta = Self::default();
+        let mut seen = Vec::new();
+        let words = words(s)?;
+        let mut words = words
+            .iter()
+            .map(String::as_str)
+            .filter(|&word| word != "and")
+            .peekable();
+
+        words.next_if_eq(&"in");
+        if words.peek().is_none() {
+            return Err(DeltaError::new(
+                DeltaErrorKind::InvalidSyntax,
+                "Invalid syntax, ex: 2 hours 30 minutes",
+            ));
+        }
+        while let Some(number) = words.next() {
+            let value = match number.chars().all(|c| c.is_numeric() || c == '.') {
+                true => number.parse().ok(),
+                false => None,
+            }
+            .ok_or_else(|| {
+                DeltaError::new(
+                    DeltaErrorKind::InvalidNumber,
+                    &format!(
+                        "Invalid number: {}, use digits (ex: 2 hours 30 minutes)",
+                        number
+                    ),
+                )
+            })?;
+            let (unit, name) = match words.next() {
+                Some("weeks" | "week" | "wks" | "wk" | "w") => (604800, "weeks"),
+                Some("days" | "day" | "d") => (86400, "days"),
+                Some("hours" | "hour" | "hrs" | "hr" | "h") => (3600, "hours"),
+                Some("minutes" | "minute" | "mins" | "min" | "m") => (60, "minutes"),
+                Some("seconds" | "second" | "secs" | "sec" | "s") => (1, "seconds"),
+                Some(word) => {
+                    return Err(DeltaError::new(
+                        DeltaErrorKind::UnknownUnit,
+                        &format!("Unknown unit: {}", word),
+                    ))
+                }
+                None => {
+                    return Err(DeltaError::new(
+                        DeltaErrorKind::MissingUnit,
+                        &format!("Missing unit after {}, ex: {} minutes", number, number),
+                    ))
+                }
+            };
+            check_order(&mut seen, unit, name)?;
+            delta.add(value, unit)?;
+        }
+
+        Ok(delta)
+    }
+
+    /// Parses the minutes of the +90 shortcut, which is a bare number without units.
+    fn from_minutes(s: &str) -> Result<Self, DeltaError> {
+        if s.is_empty() || !s.chars().all(|c| c.is_ascii_digit()) {
+            return Err(DeltaError::new(
+                DeltaErrorKind::InvalidNumber,
+                "Invalid minutes after +, ex: +90",
+            ));
+        }
+
+        match s
+            .parse::<u64>()
+            .ok()
+            .and_then(|minutes| minutes.checked_mul(60))
+        {
+            Some(0) => Err(DeltaError::new(
+                DeltaErrorKind::Empty,
+                "Zero minutes, use 0s to remind right away",
+            )),
+            Some(seconds) => Ok(Self::from_seconds(seconds)),
+            None => Err(DeltaError::new(
+                DeltaErrorKind::Overflow,
+                "Duration too large",
+            )),
+        }
+    }
+
+    /// Parses an ISO-8601 duration like PT2H30M or P1DT12H, with weeks counted as 7 days.
+    pub fn from_iso8601(s: &str) -> Result<Self, DeltaError> {
+        let invalid = || {
+            DeltaError::new(
+                DeltaErrorKind::InvalidSyntax,
+                "Invalid ISO-8601 duration, ex: PT2H30M",
+            )
+        };
+        let rest = s.trim().strip_prefix(['P', 'p']).ok_or_else(invalid)?;
+        if rest.is_empty() || rest.ends_with(['T', 't']) {
+            return Err(invalid());
+        }
+
+        let mut delta = Self::default();
+        let mut number = String::new();
+        let mut seen = Vec::new();
+        let mut time = false;
+        for c in rest.chars() {
+            let c = c.to_ascii_uppercase();
+            // Components before T are dates and after it times, so M is months before and minutes after.
+            let unit = match (c, time) {
+                ('T', false) if number.is_empty() => {
+                    time = true;
+                    continue;
+                }
+                ('.' | ',', _) | ('0'..='9', _) => {
+                    number.push(if c == ',' { '.' } else { c });
+                    continue;
+                }
+                ('W', false) => 7 * 86400,
+                ('D', false) => 86400,
+                ('H', true) => 3600,
+                ('M', true) => 60,
+                ('S', true) => 1,
+                ('Y' | 'M', false) => {
+                    return Err(DeltaError::new(
+                        DeltaErrorKind::UnsupportedUnit,
+                        "Years and months aren't supported, use days instead",
+                    ))
+                }
+                _ => return Err(invalid()),
+            };
+
+            check_order(&mut seen, unit, &c.to_string())?;
+            let value = number.parse().map_err(|_| invalid())?;
+            delta.add(value, unit)?;
+            number.clear();
+        }
+        if !number.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(delta)
+    }
+
+    /// Adds `value` units of `unit` seconds to the total, normalizing the fields so 90m is 1h30m.
+    fn add(&mut self, value: f64, unit: u64) -> Result<(), DeltaError> {
+        // Casting saturates, so anything too large for a u64 fails the check below.
+        let total = self
+            .total_seconds()
+            .checked_add((value * unit as f64).round() as u64)
+            .filter(|total| total / 86400 <= u64::from(u32::MAX))
+            .ok_or_else(|| DeltaError::new(DeltaErrorKind::Overflow, "Duration too large"))?;
+        *self = Self::from_seconds(total);
+
+        Ok(())
+    }
+}
+
+/// Deltas are equal when they add up to the same duration, so 90m equals 1h30m.
+impl PartialEq for Delta {
+    fn eq(&self, other: &Self) -> bool {
+        self.total_seconds() == other.total_seconds()
+    }
+}
+
+impl Eq for Delta {}
+
+/// Formats the normalized compact form with zero components left out, ex: 90m as 1h30m.
+impl Display for Delta {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let delta = Self::from_seconds(self.total_seconds());
+        if delta.total_seconds() == 0 {
+            return write!(f, "0s");
+        }
+
+        for (value, unit) in [
+            (delta.days / 7, "w"),
+            (delta.days % 7, "d"),
+            (delta.hours, "h"),
+            (delta.minutes, "m"),
+            (delta.seconds, "s"),
+        ] {
+            if value > 0 {
+                write!(f, "{}{}", value, unit)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Delta {
+    type Err = DeltaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Records `unit` as seen, making sure units appear at most once, from days down to seconds.
+fn check_order(seen: &mut Vec<u64>, unit: u64, name: &str) -> Result<(), DeltaError> {
+    if seen.contains(&unit) {
+        return Err(DeltaError::new(
+            DeltaErrorKind::DuplicateUnit,
+            &format!("Duplicate unit: {}", name),
+        ));
+    }
+    if seen.last().is_some_and(|&last| unit > last) {
+        return Err(DeltaError::new(
+            DeltaErrorKind::UnitOrder,
+            &format!(
+                "Unit out of order: {}, use weeks, days, hours, minutes then seconds",
+                name
+            ),
+        ));
+    }
+    seen.push(unit);
+
+    Ok(())
+}
+
+/// Splits plain English like "2 hours, 30 minutes" into lowercase numbers and words.
+fn words(s: &str) -> Result<Vec<String>, DeltaError> {
+    let mut tokens: Vec<String> = Vec::new();
+    let mut last = None;
+
+    for c in s.chars() {
+        let class = match c {
+            _ if c.is_whitespace() || c == ',' => None,
+            _ if c.is_numeric() || c == '.' => Some(true),
+            _ if c.is_alphabetic() => Some(false),
+            _ => {
+                return Err(DeltaError::new(
+                    DeltaErrorKind::InvalidSyntax,
+                    "Invalid syntax, ex: 2 hours 30 minutes",
+                ))
+            }
+        };
+        match (class, tokens.last_mut()) {
+            (None, _) => {}
+            (Some(_), Some(token)) if class == last => token.extend(c.to_lowercase()),
+            (Some(_), _) => tokens.push(c.to_lowercase().collect()),
+        }
+        last = class;
+    }
+
+    Ok(tokens)
+}
+
+/// What went wrong parsing a delta, to match on without comparing messages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeltaErrorKind {
+    /// Nothing but whitespace was given
+    Empty,
+    /// Not a delta in any of the supported forms
+    InvalidSyntax,
+    /// A number that can't be parsed, like 1.2.3 or two
+    InvalidNumber,
+    /// A unit without a valid number before it, like w3d
+    InvalidWeeks,
+    /// Like InvalidWeeks, for days
+    InvalidDays,
+    /// Like InvalidWeeks, for hours, ex: h30m
+    InvalidHours,
+    /// Like InvalidWeeks, for minutes
+    InvalidMinutes,
+    /// Like InvalidWeeks, for seconds
+    InvalidSeconds,
+    /// A word that isn't a unit, like 2 fortnights
+    UnknownUnit,
+    /// A number without a unit after it
+    MissingUnit,
+    /// A unit that can't be used, like ISO-8601 years and months
+    UnsupportedUnit,
+    /// A unit given more than once
+    DuplicateUnit,
+    /// Units not going from weeks down to seconds
+    UnitOrder,
+    /// Too long to fit in a Delta or the calendar
+    Overflow,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct DeltaError {
+    pub kind: DeltaErrorKind,
+    message: String,
+}
+
+impl DeltaError {
+    pub fn new(kind: DeltaErrorKind, message: &str) -> Self {
+        Self {
+            kind,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl Display for DeltaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for DeltaError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delta(days: u32, hours: u32, minutes: u32, seconds: u32) -> Delta {
+        Delta {
+            days,
+            hours,
+            minutes,
+            seconds,
+        }
+    }
+
+    fn error(s: &str) -> String {
+        Delta::parse(s).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn parses_valid_deltas() {
+        let parsed: Delta = "2h30m".parse().unwrap();
+        assert_eq!((parsed.hours, parsed.minutes), (2, 30));
+        let parsed: Delta = "45m".parse().unwrap();
+        assert_eq!((parsed.hours, parsed.minutes), (0, 45));
+        let parsed: Delta = "3h".parse().unwrap();
+        assert_eq!((parsed.hours, parsed.minutes), (3, 0));
+        assert_eq!("1d2h30m15s".parse::<Delta>().unwrap(), delta(1, 2, 30, 15));
+    }
+
+    #[test]
+    fn rejects_empty_string() {
+        assert_eq!(error(""), "Empty duration, use 0s to remind right away");
+        assert_eq!(error(" \t"), "Empty duration, use 0s to remind right away");
+        assert_eq!("0s".parse::<Delta>().unwrap().total_seconds(), 0);
+    }
+
+    #[test]
+    fn rejects_missing_units() {
+        assert_eq!(error("30"), "Missing unit after 30, ex: 30m");
+        assert_eq!(error("2h30"), "Missing unit after 30, ex: 30m");
+        assert_eq!(error("1.5"), "Missing unit after 1.5, ex: 1.5m");
+        assert_eq!(
+            Delta::parse("2h 30").unwrap_err().kind,
+            DeltaErrorKind::MissingUnit
+        );
+    }
+
+    #[test]
+    fn parses_minutes_shortcut() {
+        assert_eq!(Delta::parse("+90").unwrap(), delta(0, 1, 30, 0));
+        assert_eq!(Delta::parse(" +5 ").unwrap(), delta(0, 0, 5, 0));
+        assert_eq!(Delta::parse("+1440").unwrap(), delta(1, 0, 0, 0));
+        assert_eq!(error("+0"), "Zero minutes, use 0s to remind right away");
+        assert_eq!(
+            Delta::parse("+000").unwrap_err().kind,
+            DeltaErrorKind::Empty
+        );
+        assert_eq!(error("+abc"), "Invalid minutes after +, ex: +90");
+        assert_eq!(error("+"), "Invalid minutes after +, ex: +90");
+        assert_eq!(error("+90m"), "Invalid minutes after +, ex: +90");
+        assert_eq!(error("+-5"), "Invalid minutes after +, ex: +90");
+        assert_eq!(
+            Delta::parse("+99999999999999999999").unwrap_err().kind,
+            DeltaErrorKind::Overflow
+        );
+    }
+
+    #[test]
+    fn parses_weeks() {
+        assert_eq!("2w".parse::<Delta>().unwrap(), delta(14, 0, 0, 0));
+        assert_eq!("1w3d".parse::<Delta>().unwrap(), delta(10, 0, 0, 0));
+        assert_eq!(Delta::from_natural("1 week").unwrap(), delta(7, 0, 0, 0));
+        assert_eq!(delta(10, 0, 0, 0).to_string(), "1w3d");
+    }
+
+    #[test]
+    fn normalizes_components() {
+        let parsed: Delta = "200m".parse().unwrap();
+        assert_eq!((parsed.hours, parsed.minutes), (3, 20));
+        assert_eq!(parsed.to_string(), "3h20m");
+        assert_eq!("36h90s".parse::<Delta>().unwrap(), delta(1, 12, 1, 30));
+    }
+
+    #[test]
+    fn parses_fractions() {
+        assert_eq!("1.5h".parse::<Delta>().unwrap(), delta(0, 1, 30, 0));
+        assert_eq!("0.5d".parse::<Delta>().unwrap(), delta(0, 12, 0, 0));
+        assert_eq!(error("1.2.3h"), "Invalid number, too many decimal points");
+    }
+
+    #[test]
+    fn ignores_case_and_whitespace() {
+        assert_eq!("2H 30M".parse::<Delta>().unwrap(), delta(0, 2, 30, 0));
+        assert_eq!(" 1d\t5s ".parse::<Delta>().unwrap(), delta(1, 0, 0, 5));
+        for mixed in ["2H30M", "2h 30m", "2h30m", "2H 30m", " 2 h 3 0 m "] {
+            assert_eq!(mixed.parse::<Delta>().unwrap(), delta(0, 2, 30, 0));
+        }
+        assert_eq!(error("2X"), "Invalid syntax, ex: 1d2h30m15s");
+        assert_eq!(error("2h 3x"), "Invalid syntax, ex: 1d2h30m15s");
+    }
+
+    #[test]
+    fn accepts_commas() {
+        assert_eq!("2h,30m".parse::<Delta>().unwrap(), delta(0, 2, 30, 0));
+        assert_eq!("1,5h".parse::<Delta>().unwrap(), delta(0, 1, 30, 0));
+        assert_eq!("2h, 30m".parse::<Delta>().unwrap(), delta(0, 2, 30, 0));
+        assert_eq!("1,5h,30m".parse::<Delta>().unwrap(), delta(0, 2, 0, 0));
+        // Whether these commas separate units or mark decimals can't be told, so they still fail.
+        assert_eq!(error("1,5,5h"), "Invalid number, too many decimal points");
+        assert_eq!(error("2h,30"), "Missing unit after 30, ex: 30m");
+    }
+
+    #[test]
+    fn rejects_invalid_deltas() {
+        assert_eq!(error("2x"), "Invalid syntax, ex: 1d2h30m15s");
+        assert_eq!(error("h30m"), "Invalid hours");
+        assert_eq!(error("2hh"), "Duplicate unit: h");
+    }
+
+    #[test]
+    fn rejects_duplicate_and_unordered_units() {
+        assert_eq!(error("2h3h"), "Duplicate unit: h");
+        assert_eq!(error("1d2h3H"), "Duplicate unit: h");
+        assert_eq!(
+            error("30m2h"),
+            "Unit out of order: h, use weeks, days, hours, minutes then seconds"
+        );
+        assert_eq!(
+            error("1s1d"),
+            "Unit out of order: d, use weeks, days, hours, minutes then seconds"
+        );
+    }
+
+    #[test]
+    fn reports_error_kinds() {
+        let kind = |s| Delta::parse(s).unwrap_err().kind;
+        assert_eq!(kind(""), DeltaErrorKind::Empty);
+        assert_eq!(kind("2x"), DeltaErrorKind::InvalidSyntax);
+        assert_eq!(kind("h30m"), DeltaErrorKind::InvalidHours);
+        assert_eq!(kind("2h.m"), DeltaErrorKind::InvalidMinutes);
+        assert_eq!(kind("2hh"), DeltaErrorKind::DuplicateUnit);
+        assert_eq!(kind("30m2h"), DeltaErrorKind::UnitOrder);
+        assert_eq!(kind("5000000000d"), DeltaErrorKind::Overflow);
+        assert_eq!(kind("P1Y"), DeltaErrorKind::UnsupportedUnit);
+        assert_eq!(
+            Delta::from_natural("2 fortnights").unwrap_err().kind,
+            DeltaErrorKind::UnknownUnit
+        );
+    }
+
+    #[test]
+    fn rejects_overflow() {
+        assert_eq!(error("5000000000d"), "Duration too large");
+        // u32::MAX days is the longest delta, one second more overflows.
+        assert_eq!(
+            "4294967295d".parse::<Delta>().unwrap(),
+            delta(u32::MAX, 0, 0, 0)
+        );
+        assert_eq!(error("4294967296d"), "Duration too large");
+        assert_eq!(error("4294967295d24h"), "Duration too large");
+        assert_eq!(error("99999999999999999999999s"), "Duration too large");
+        assert_eq!(
+            delta(u32::MAX, 0, 0, 0)
+                .after(Local::now())
+                .unwrap_err()
+                .to_string(),
+            "Duration too large"
+        );
+    }
+
+    #[test]
+    fn counts_total_seconds() {
+        assert_eq!(Delta::default().total_seconds(), 0);
+        assert_eq!(delta(0, 0, 0, 0).total_seconds(), 0);
+        assert_eq!(delta(0, 0, 0, 1).total_seconds(), 1);
+        assert_eq!(delta(0, 0, 0, 59).total_seconds(), 59);
+        assert_eq!(delta(0, 0, 1, 0).total_seconds(), 60);
+        assert_eq!(delta(0, 0, 59, 59).total_seconds(), 3599);
+        assert_eq!(delta(0, 1, 0, 0).total_seconds(), 3600);
+        assert_eq!(delta(0, 23, 59, 59).total_seconds(), 86399);
+        assert_eq!(delta(1, 0, 0, 0).total_seconds(), 86400);
+        assert_eq!(delta(1, 1, 1, 1).total_seconds(), 90061);
+        assert_eq!(
+            delta(u32::MAX, u32::MAX, u32::MAX, u32::MAX).total_seconds(),
+            u64::from(u32::MAX) * 90061
+        );
+    }
+
+    #[test]
+    fn splits_seconds() {
+        assert_eq!(Delta::from_seconds(90061), delta(1, 1, 1, 1));
+    }
+
+    #[test]
+    fn displays_compact_form() {
+        assert_eq!(delta(0, 0, 90, 0).to_string(), "1h30m");
+        assert_eq!(delta(2, 0, 0, 5).to_string(), "2d5s");
+        assert_eq!(delta(0, 0, 0, 0).to_string(), "0s");
+    }
+
+    #[test]
+    fn round_trips_display() {
+        for delta in [
+            delta(0, 0, 0, 0),
+            delta(0, 0, 0, 59),
+            delta(0, 0, 90, 0),
+            delta(2, 0, 0, 5),
+            delta(10, 23, 59, 59),
+            delta(u32::MAX, 23, 59, 59),
+        ] {
+            assert_eq!(Delta::from_str(&delta.to_string()).unwrap(), delta);
+        }
+    }
+
+    #[test]
+    fn parses_iso8601() {
+        assert_eq!("PT2H30M".parse::<Delta>().unwrap(), delta(0, 2, 30, 0));
+        assert_eq!("P1DT12H".parse::<Delta>().unwrap(), delta(1, 12, 0, 0));
+        assert_eq!("P1W".parse::<Delta>().unwrap(), delta(7, 0, 0, 0));
+        assert_eq!("PT1,5M".parse::<Delta>().unwrap(), delta(0, 0, 1, 30));
+        for invalid in ["P", "PT", "P1DT", "PT2H30", "P2H", "PTT1H"] {
+            assert_eq!(error(invalid), "Invalid ISO-8601 duration, ex: PT2H30M");
+        }
+        assert_eq!(
+            error("P1M"),
+            "Years and months aren't supported, use days instead"
+        );
+        assert_eq!(error("PT1M1M"), "Duplicate unit: M");
+    }
+
+    #[test]
+    fn parses_natural_language() {
+        assert_eq!(
+            Delta::from_natural("in 2 hours and 30 minutes").unwrap(),
+            delta(0, 2, 30, 0)
+        );
+        assert_eq!(
+            Delta::from_natural("1 day, 5 secs").unwrap(),
+            delta(1, 0, 0, 5)
+        );
+        assert_eq!(
+            Delta::from_natural("two hours").unwrap_err().to_string(),
+            "Invalid number: two, use digits (ex: 2 hours 30 minutes)"
+        );
+        assert_eq!(
+            Delta::from_natural("3").unwrap_err().to_string(),
+            "Missing unit after 3, ex: 3 minutes"
+        );
+    }
+}
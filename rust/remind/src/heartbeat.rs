@@ -0,0 +1,65 @@
+use std::{
+    fs,
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+    sync::mpsc::{self, RecvTimeoutError, Sender},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use chrono::Local;
+
+/// Rewrites a file with the current time on another thread, so a monitor can tell we're alive.
+pub struct Heartbeat {
+    path: PathBuf,
+    stop: Sender<()>,
+    thread: JoinHandle<()>,
+}
+
+impl Heartbeat {
+    /// Beats right away, so a bad path is reported before waiting, then every `every`.
+    pub fn start(path: &Path, every: Duration) -> io::Result<Self> {
+        beat(path)?;
+
+        let (stop, stopped) = mpsc::channel();
+        let thread = thread::spawn({
+            let path = path.to_path_buf();
+            move || {
+                while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(every) {
+                    if let Err(e) = beat(&path) {
+                        eprintln!("{}", e);
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            stop,
+            thread,
+        })
+    }
+
+    /// Stops beating and removes the file, so it's only left behind when we didn't finish.
+    pub fn stop(self) -> io::Result<()> {
+        drop(self.stop);
+        let _ = self.thread.join();
+
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(io::Error::new(
+                e.kind(),
+                format!("Could not remove heartbeat {}: {}", self.path.display(), e),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn beat(path: &Path) -> io::Result<()> {
+    fs::write(path, format!("{}\n", Local::now().to_rfc3339())).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!("Could not write heartbeat {}: {}", path.display(), e),
+        )
+    })
+}
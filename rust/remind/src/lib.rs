@@ -0,0 +1,1020 @@
+//! Scheduling logic of the remind tool, for use from other programs.
+
+use std::{
+    io::{self, ErrorKind, IsTerminal, Stdout, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::{Arc, Condvar, LazyLock, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local, NaiveTime};
+use clap::ValueEnum;
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    terminal,
+};
+use tracing::debug;
+
+pub use clock::{Clock, SystemClock};
+pub use delta::{Delta, DeltaError, DeltaErrorKind};
+pub use notifier::{bell, Desktop, Exec, Fired, Log, Notifier, Sound, Terminal};
+
+mod clock;
+mod delta;
+mod notifier;
+mod sound;
+pub mod storage;
+
+static STATE: Mutex<State> = Mutex::new(State {
+    cancelled: false,
+    paused: false,
+});
+static WAKE: Condvar = Condvar::new();
+/// How often waiting for a wall clock time checks the clock again, so a reminder is at most
+/// this late after the clock is changed or the machine wakes up from suspend.
+const CLOCK_POLL: Duration = Duration::from_secs(1);
+/// Every trigger writes through this, so reminders firing together don't interleave their lines.
+static OUTPUT: LazyLock<Mutex<Stdout>> = LazyLock::new(|| Mutex::new(io::stdout()));
+
+/// Shared by every waiting reminder, guarded by STATE and signalled through WAKE.
+struct State {
+    cancelled: bool,
+    paused: bool,
+}
+
+fn state() -> MutexGuard<'static, State> {
+    STATE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Output format
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum Format {
+    /// Human readable output
+    Text,
+    /// One JSON object per line
+    Json,
+    /// Only a tab separated timestamp and message line per reminder, without a bell
+    PlainLine,
+}
+
+/// What to clear before printing the first message
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum Clear {
+    /// Nothing, keeping the scrollback intact
+    None,
+    /// The whole screen
+    Screen,
+    /// Only the current line
+    Line,
+}
+
+/// How urgent a reminder is, for desktop notifications and the bell
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum Urgency {
+    /// Notification that can be shown less prominently
+    Low,
+    /// Regular notification and bell
+    Normal,
+    /// Also rings the terminal bell three times as often
+    Critical,
+}
+
+/// Time of day when reminders don't make a sound, ex: 22:00 to 07:00.
+#[derive(Clone, Copy)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl QuietHours {
+    /// Whether `time` falls inside the window, which wraps past midnight when it ends before it
+    /// starts.
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        match self.start <= self.end {
+            true => self.start <= time && time < self.end,
+            false => self.start <= time || time < self.end,
+        }
+    }
+}
+
+/// How a reminder behaves once it triggers.
+#[derive(Clone)]
+pub struct Options {
+    /// Run reminder only once
+    pub once: bool,
+    /// Stop after reminding this many times
+    pub count: Option<u64>,
+    /// Time between repeated reminders
+    pub repeat: Duration,
+    /// Multiplies the time between repeated reminders after each one
+    pub backoff: f64,
+    /// Longest time between repeated reminders once `backoff` applies
+    pub repeat_max: Option<Duration>,
+    /// Random offset of up to this long, added to or taken off every repeat
+    pub jitter: Option<Duration>,
+    /// Stop repeating once this time is reached
+    pub until: Option<DateTime<Local>>,
+    /// Press s to snooze repeated reminders for this long, or q to quit
+    pub snooze: Option<Duration>,
+    /// Keep repeating until Enter is pressed
+    pub until_ack: bool,
+    /// Outputs fired in turn every time the reminder triggers, ex: the terminal and a log
+    pub notifiers: Vec<Arc<dyn Notifier>>,
+    /// Only print the reminder during these hours, without a bell, sound or notification
+    pub quiet_hours: Option<QuietHours>,
+    /// Hold off on firing while this file exists, checking again after every repeat
+    pub dnd_file: Option<PathBuf>,
+    /// Name shown in front of the reminder, ex: [standup] Time is up!
+    pub label: Option<String>,
+    /// Messages shown in turn on every repeat instead of the reminder message
+    pub messages: Vec<String>,
+    /// Shell command run every time the reminder triggers, its output replacing the message
+    pub message_cmd: Option<String>,
+    /// Print in bold bright colors, the terminal notifier being told separately
+    pub color: bool,
+    /// When the reminder was set, used by the {elapsed} placeholder
+    pub set_at: Option<DateTime<Local>>,
+    /// Show how long ago the reminder was set once it triggers
+    pub show_elapsed: bool,
+    /// Output format
+    pub format: Format,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            once: false,
+            count: None,
+            repeat: Duration::from_secs(30),
+            backoff: 1.0,
+            repeat_max: None,
+            jitter: None,
+            until: None,
+            snooze: None,
+            until_ack: false,
+            notifiers: vec![Arc::new(Terminal::new(
+                Format::Text,
+                Clear::Screen,
+                false,
+                bell(1, Urgency::Normal),
+                bell(1, Urgency::Normal),
+            ))],
+            quiet_hours: None,
+            dnd_file: None,
+            label: None,
+            messages: Vec::new(),
+            message_cmd: None,
+            color: false,
+            set_at: None,
+            show_elapsed: false,
+            format: Format::Text,
+        }
+    }
+}
+
+/// A message shown once its delta has passed.
+pub struct Reminder {
+    pub delta: Delta,
+    pub message: String,
+    pub options: Options,
+}
+
+impl Reminder {
+    pub fn new(delta: Delta, message: &str) -> Self {
+        Self {
+            delta,
+            message: message.to_string(),
+            options: Options::default(),
+        }
+    }
+
+    /// Time to wait before the reminder triggers.
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs(self.delta.total_seconds())
+    }
+
+    /// Waits for the reminder to be due, then triggers it, returning how many times it fired.
+    pub fn run(&self) -> io::Result<u64> {
+        sleep(self.duration())?;
+
+        self.trigger()
+    }
+
+    /// Message shown the first time the reminder triggers, filled in for `now` without running
+    /// the message command, ex: for a heads-up before it.
+    pub fn first_message(&self, now: DateTime<Local>) -> String {
+        let message = self.options.messages.first().unwrap_or(&self.message);
+        self.fill(message, now, 0)
+    }
+
+    /// Fills in the placeholders of `message` for the trigger after `fired` others.
+    fn fill(&self, message: &str, now: DateTime<Local>, fired: u64) -> String {
+        // Counts the repeats still to come after this one, which never run out without a count.
+        let count = match self.options.once {
+            true => Some(1),
+            false => self.options.count,
+        };
+        let remaining = count.map_or("∞".to_string(), |count| {
+            count.saturating_sub(fired + 1).to_string()
+        });
+
+        render(message, now, self.options.set_at).replace("{remaining}", &remaining)
+    }
+
+    /// Fires every notifier of the reminder, repeating it as configured.
+    ///
+    /// Returns how many times the reminder fired.
+    pub fn trigger(&self) -> io::Result<u64> {
+        self.trigger_with(&SystemClock)
+    }
+
+    /// Triggers like `trigger`, taking the time and sleeping between repeats with `clock`.
+    pub fn trigger_with(&self, clock: &impl Clock) -> io::Result<u64> {
+        let options = &self.options;
+
+        // Measured at the first trigger, so repeats keep showing the same value.
+        let elapsed = options
+            .set_at
+            .filter(|_| options.show_elapsed)
+            .map(|set_at| {
+                Delta::from_seconds((clock.now() - set_at).num_seconds().max(0).unsigned_abs())
+            });
+
+        // Key presses can only be read from an interactive terminal.
+        let snooze = options.snooze.filter(|_| io::stdin().is_terminal());
+        let ack = options.until_ack && io::stdin().is_terminal();
+        let (keys, prompt) = match (snooze.is_some(), ack) {
+            (true, true) => (
+                &['s', 'q', '\n'][..],
+                "Press Enter to stop, s to snooze or q to quit.\n",
+            ),
+            (true, false) => (&['s', 'q'][..], "Press s to snooze or q to quit.\n"),
+            (false, true) => (&['\n'][..], "Press Enter to stop.\n"),
+            (false, false) => (&[][..], ""),
+        };
+        let mut repeat = options.repeat;
+        let mut fired = 0;
+        loop {
+            // Do not disturb doesn't count as firing, so the file being removed fires it as usual.
+            if options.dnd_file.as_deref().is_some_and(Path::exists) {
+                if options.until.is_some_and(|until| clock.now() >= until) {
+                    break;
+                }
+                debug!(?repeat, "Holding off for do not disturb");
+                clock.sleep(repeat)?;
+                continue;
+            }
+
+            let message = match options.messages.is_empty() {
+                true => &self.message,
+                false => &options.messages[fired as usize % options.messages.len()],
+            };
+            let output = options.message_cmd.as_deref().and_then(command_message);
+            let message = self.fill(output.as_deref().unwrap_or(message), clock.now(), fired);
+
+            let now = Fired {
+                message: &message,
+                label: options.label.as_deref(),
+                elapsed: elapsed.as_ref(),
+                at: clock.now(),
+                count: fired,
+                quiet: options
+                    .quiet_hours
+                    .is_some_and(|quiet| quiet.contains(clock.now().time())),
+            };
+            debug!(count = fired, at = %now.at, quiet = now.quiet, "Triggered");
+            for notifier in &options.notifiers {
+                notifier.fire(&now)?;
+            }
+            fired += 1;
+            if options.once
+                || options.count.is_some_and(|count| fired >= count)
+                || options.until.is_some_and(|until| {
+                    (until - clock.now())
+                        .to_std()
+                        .map_or(true, |left| left < repeat)
+                })
+            {
+                break;
+            }
+            let wait = match options.jitter {
+                Some(jitter) => {
+                    Duration::from_secs(self::jitter(repeat.as_secs(), jitter.as_secs()))
+                }
+                None => repeat,
+            };
+            match keys.is_empty() {
+                false => {
+                    if fired == 1 && options.format == Format::Text {
+                        emit(&OUTPUT, &[prompt])?;
+                    }
+                    debug!(?wait, "Waiting for a key until the next repeat");
+                    match (wait_for_key(wait, keys)?, snooze) {
+                        (Some('s'), Some(snooze)) => {
+                            debug!(?snooze, "Snoozing");
+                            clock.sleep(snooze)?;
+                        }
+                        (Some(_), _) => break,
+                        (None, _) => {}
+                    }
+                }
+                true => {
+                    debug!(?wait, "Sleeping until the next repeat");
+                    clock.sleep(wait)?;
+                }
+            }
+            repeat = backoff(repeat, options.backoff, options.repeat_max);
+        }
+
+        Ok(fired)
+    }
+}
+
+/// Fills in the {time}, {date} and {elapsed} placeholders, leaving any other braces as they are.
+///
+/// {elapsed} is only filled in when `set_at` is known.
+fn render(message: &str, now: DateTime<Local>, set_at: Option<DateTime<Local>>) -> String {
+    let message = message
+        .replace("{time}", &now.format("%H:%M").to_string())
+        .replace("{date}", &now.format("%Y-%m-%d").to_string());
+
+    match set_at {
+        Some(set_at) => {
+            let elapsed = (now - set_at).num_seconds().max(0).unsigned_abs();
+            message.replace("{elapsed}", &Delta::from_seconds(elapsed).to_string())
+        }
+        None => message,
+    }
+}
+
+/// Writes every part while holding `out`, flushing before letting anyone else write.
+fn emit<W: Write>(out: &Mutex<W>, parts: &[&str]) -> io::Result<()> {
+    let mut out = out.lock().unwrap_or_else(|e| e.into_inner());
+    for part in parts {
+        out.write_all(part.as_bytes())?;
+    }
+
+    out.flush()
+}
+
+/// Clears the screen or line before writing every part like `emit`, without letting anyone in
+/// between.
+fn emit_cleared<W: Write>(out: &Mutex<W>, clear: Clear, parts: &[&str]) -> io::Result<()> {
+    let mut out = out.lock().unwrap_or_else(|e| e.into_inner());
+    clear_screen(&mut *out, clear)?;
+    for part in parts {
+        out.write_all(part.as_bytes())?;
+    }
+
+    out.flush()
+}
+
+/// Clears the screen or line, moving to its start.
+///
+/// Older Windows consoles don't understand the escape codes, so crossterm clears them through
+/// the console API instead.
+fn clear_screen(out: &mut impl Write, clear: Clear) -> io::Result<()> {
+    match clear {
+        Clear::None => Ok(()),
+        Clear::Screen => crossterm::queue!(
+            out,
+            terminal::Clear(terminal::ClearType::All),
+            cursor::MoveTo(0, 0)
+        ),
+        Clear::Line => crossterm::queue!(
+            out,
+            terminal::Clear(terminal::ClearType::CurrentLine),
+            cursor::MoveToColumn(0)
+        ),
+    }
+}
+
+/// Waits for every reminder in turn with `wait`, then triggers it with `clock`.
+///
+/// `wait` returns how long it was paused for, which pushes back every reminder still waiting.
+/// Returns how many times the reminders fired in total.
+pub fn run_queue(
+    clock: &impl Clock,
+    queue: &mut [(Reminder, DateTime<Local>)],
+    mut wait: impl FnMut(&Reminder, DateTime<Local>) -> io::Result<Duration>,
+) -> io::Result<u64> {
+    let mut paused = Duration::ZERO;
+    let mut fired = 0;
+    for (reminder, target) in queue {
+        paused += wait(reminder, *target + paused)?;
+        reminder.options.until = reminder.options.until.map(|until| until + paused);
+        fired += reminder.trigger_with(clock)?;
+    }
+
+    Ok(fired)
+}
+
+/// Multiplies `repeat` by `factor`, keeping it between a second and `max`.
+fn backoff(repeat: Duration, factor: f64, max: Option<Duration>) -> Duration {
+    let repeat = Duration::try_from_secs_f64(repeat.as_secs_f64() * factor)
+        .unwrap_or(Duration::MAX)
+        .max(Duration::from_secs(1));
+
+    max.map_or(repeat, |max| repeat.min(max))
+}
+
+/// Adds a random offset between -`jitter` and +`jitter` to `seconds`, keeping at least a second.
+pub fn jitter(seconds: u64, jitter: u64) -> u64 {
+    let offset = rand::random_range(0..=jitter.saturating_mul(2));
+
+    seconds.saturating_add(offset).saturating_sub(jitter).max(1)
+}
+
+/// Checks that an audio file loads, so a bad `sound` is reported before waiting.
+///
+/// Builds without the sound feature warn once instead, since they ring the bell when it triggers.
+pub fn check_sound(path: &Path) -> io::Result<()> {
+    sound::check(path).map_err(|e| {
+        io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("Could not load sound {}: {}", path.display(), e),
+        )
+    })?;
+    #[cfg(not(feature = "sound"))]
+    {
+        static WARNED: std::sync::Once = std::sync::Once::new();
+        WARNED.call_once(|| {
+            eprintln!(
+                "Warning: remind was built without the sound feature, so the bell rings instead \
+                 (reinstall it with --features sound)."
+            )
+        });
+    }
+
+    Ok(())
+}
+
+/// Wraps `text` in bold bright yellow when `color` is set.
+pub fn highlight(text: &str, color: bool) -> String {
+    match color {
+        true => format!("\x1b[1;93m{}\x1b[0m", text),
+        false => text.to_string(),
+    }
+}
+
+/// Runs `command` for a message, warning and returning None when it fails or prints nothing.
+fn command_message(command: &str) -> Option<String> {
+    match shell(command).stderr(Stdio::inherit()).output() {
+        Ok(output) if output.status.success() => {
+            let message = String::from_utf8_lossy(&output.stdout)
+                .trim_end()
+                .to_string();
+            if message.is_empty() {
+                eprintln!("Command {} printed no message", command);
+                return None;
+            }
+            Some(message)
+        }
+        Ok(output) => {
+            eprintln!("Command {} failed: {}", command, output.status);
+            None
+        }
+        Err(e) => {
+            eprintln!("Could not run {}: {}", command, e);
+            None
+        }
+    }
+}
+
+/// Builds a command running `command` through the platform shell.
+fn shell(command: &str) -> Command {
+    let (shell, flag) = match cfg!(windows) {
+        true => ("cmd", "/C"),
+        false => ("sh", "-c"),
+    };
+    let mut shell = Command::new(shell);
+    shell.arg(flag).arg(command);
+
+    shell
+}
+
+/// Cancels all reminders, waking up any thread sleeping on them.
+pub fn cancel() {
+    state().cancelled = true;
+    WAKE.notify_all();
+}
+
+/// Pauses or resumes every countdown, keeping the time left while paused.
+pub fn set_paused(paused: bool) {
+    state().paused = paused;
+    WAKE.notify_all();
+}
+
+/// Whether reminders were cancelled, for callers waiting on their own.
+pub fn is_cancelled() -> bool {
+    state().cancelled
+}
+
+/// Whether countdowns are paused, for callers waiting on their own.
+pub fn is_paused() -> bool {
+    state().paused
+}
+
+/// The error waiting fails with once reminders are cancelled.
+pub fn cancelled() -> io::Error {
+    io::Error::new(ErrorKind::Interrupted, "Reminder cancelled")
+}
+
+/// Sleeps for `duration`, failing with `ErrorKind::Interrupted` as soon as reminders are cancelled.
+///
+/// Time spent paused doesn't count towards `duration`.
+pub fn sleep(duration: Duration) -> io::Result<()> {
+    pausable_sleep(duration).map(|_| ())
+}
+
+/// Sleeps like `sleep`, returning how long it was paused for.
+fn pausable_sleep(duration: Duration) -> io::Result<Duration> {
+    // Durations too long for an Instant are as good as forever.
+    let forever = || Instant::now() + Duration::from_secs(u64::from(u32::MAX));
+    let mut deadline = Instant::now().checked_add(duration).unwrap_or_else(forever);
+    let mut paused = Duration::ZERO;
+    let mut state = state();
+
+    loop {
+        if state.cancelled {
+            return Err(cancelled());
+        }
+        if state.paused {
+            let start = Instant::now();
+            state = WAKE
+                .wait_while(state, |state| state.paused && !state.cancelled)
+                .unwrap_or_else(|e| e.into_inner());
+            paused += start.elapsed();
+            deadline += start.elapsed();
+            continue;
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Ok(paused);
+        }
+        (state, _) = WAKE
+            .wait_timeout_while(state, remaining, |state| !state.cancelled && !state.paused)
+            .unwrap_or_else(|e| e.into_inner());
+    }
+}
+
+/// Sleeps until `clock` reaches `target`, checking it every CLOCK_POLL rather than sleeping once.
+///
+/// Pausing pushes `target` back, and the total time spent paused is returned.
+pub fn sleep_until(clock: &impl Clock, mut target: DateTime<Local>) -> io::Result<Duration> {
+    debug!(%target, left = ?(target - clock.now()).to_std().unwrap_or_default(), "Sleeping until");
+    let mut paused = Duration::ZERO;
+    loop {
+        let left = (target - clock.now()).to_std().unwrap_or_default();
+        if left.is_zero() {
+            return Ok(paused);
+        }
+        let more = clock.sleep(left.min(CLOCK_POLL))?;
+        paused += more;
+        target += more;
+    }
+}
+
+/// Whole seconds in `left`, rounded up so a countdown ends on 00:00:01 rather than 00:00:00.
+pub fn seconds_left(left: Duration) -> u64 {
+    left.as_secs() + u64::from(left.subsec_nanos() > 0)
+}
+
+/// Formats seconds the way countdowns show them, ex: 01:30:05
+pub fn hms(seconds: u64) -> String {
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+/// Sleeps until `target`, counting down the time left on a single line if `progress` is set.
+///
+/// With `since`, the line counts up the time elapsed from it instead, like a stopwatch.
+/// The countdown is only shown when stdout is a terminal, otherwise this just sleeps.
+/// Pausing pushes `target` back, and the total time spent paused is returned.
+pub fn wait_until(
+    target: DateTime<Local>,
+    progress: bool,
+    since: Option<DateTime<Local>>,
+) -> io::Result<Duration> {
+    let mut target = target;
+    let left = |target: DateTime<Local>| (target - Local::now()).to_std().unwrap_or_default();
+    if !progress || !io::stdout().is_terminal() {
+        return sleep_until(&SystemClock, target);
+    }
+
+    debug!(%target, left = ?left(target), "Counting down until");
+    // Pausing pushes `target` back without moving `since`, so the elapsed time stands still.
+    let total = since.map(|since| (target - since).num_seconds().max(0).unsigned_abs());
+    let mut paused = Duration::ZERO;
+    let result = loop {
+        let remaining = left(target);
+        if remaining.is_zero() {
+            break Ok(paused);
+        }
+        let seconds = seconds_left(remaining);
+        let shown = total.map_or(seconds, |total| total.saturating_sub(seconds));
+        let countdown = hms(shown);
+        // The width is read on every tick, so the line never wraps after the terminal is resized.
+        let width = match terminal::size() {
+            Ok((columns, _)) if columns > 0 => usize::from(columns),
+            _ => usize::MAX,
+        };
+        let countdown: String = countdown.chars().take(width).collect();
+        print!("\r\x1b[2K{}", countdown);
+        io::stdout().flush()?;
+        match pausable_sleep(remaining - Duration::from_secs(seconds - 1)) {
+            Ok(more) => {
+                paused += more;
+                target += more;
+            }
+            Err(e) => break Err(e),
+        }
+    };
+    print!("\r\x1b[2K");
+    io::stdout().flush()?;
+
+    result
+}
+
+/// Sleeps for `duration` unless one of `keys` is pressed first, Enter being '\n'.
+///
+/// Ctrl-C cancels every reminder, like it does outside of raw mode.
+fn wait_for_key(duration: Duration, keys: &[char]) -> io::Result<Option<char>> {
+    let deadline = Instant::now()
+        .checked_add(duration)
+        .unwrap_or_else(|| Instant::now() + Duration::from_secs(u64::from(u32::MAX)));
+
+    terminal::enable_raw_mode()?;
+    let key = loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break Ok(None);
+        }
+        if state().cancelled {
+            break Err(cancelled());
+        }
+        // Poll in short steps so cancelling is noticed while waiting.
+        match event::poll(remaining.min(Duration::from_millis(100))).and_then(|ready| match ready {
+            true => event::read().map(Some),
+            false => Ok(None),
+        }) {
+            Ok(Some(Event::Key(KeyEvent {
+                code,
+                modifiers,
+                kind: KeyEventKind::Press,
+                ..
+            }))) => match code {
+                // Raw mode swallows the signal, so Ctrl-C is read as a key instead.
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    cancel();
+                    break Err(cancelled());
+                }
+                KeyCode::Char(c) if keys.contains(&c) => break Ok(Some(c)),
+                KeyCode::Enter if keys.contains(&'\n') => break Ok(Some('\n')),
+                _ => {}
+            },
+            Ok(_) => {}
+            Err(e) => break Err(e),
+        }
+    };
+    terminal::disable_raw_mode()?;
+
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, env, fs, process, thread};
+
+    use chrono::TimeZone;
+
+    use super::*;
+
+    /// Only moves forward when slept on, jumping by `jump` once on the first sleep.
+    ///
+    /// No real time passes, so hours of waiting are tested right away.
+    struct MockClock {
+        now: Cell<DateTime<Local>>,
+        jump: Cell<chrono::Duration>,
+        slept: Cell<Duration>,
+    }
+
+    impl MockClock {
+        fn new(jump: chrono::Duration) -> Self {
+            Self {
+                now: Cell::new(Local.with_ymd_and_hms(2026, 3, 1, 14, 0, 0).unwrap()),
+                jump: Cell::new(jump),
+                slept: Cell::new(Duration::ZERO),
+            }
+        }
+    }
+
+    impl Clock for MockClock {
+        fn now(&self) -> DateTime<Local> {
+            self.now.get()
+        }
+
+        fn sleep(&self, duration: Duration) -> io::Result<Duration> {
+            self.slept.set(self.slept.get() + duration);
+            self.now
+                .set(self.now.get() + duration + self.jump.replace(chrono::Duration::zero()));
+
+            Ok(Duration::ZERO)
+        }
+    }
+
+    #[test]
+    fn catches_up_with_clock_jumps() {
+        // Suspending for 20 minutes leaves 9 minutes and 59 seconds more to sleep.
+        let clock = MockClock::new(chrono::Duration::minutes(20));
+        let target = clock.now() + chrono::Duration::minutes(30);
+        assert_eq!(sleep_until(&clock, target).unwrap(), Duration::ZERO);
+        assert_eq!(clock.now(), target);
+        assert_eq!(clock.slept.get(), Duration::from_secs(600));
+
+        // Setting the clock back 5 minutes makes up for them.
+        let clock = MockClock::new(chrono::Duration::minutes(-5));
+        let target = clock.now() + chrono::Duration::minutes(30);
+        sleep_until(&clock, target).unwrap();
+        assert_eq!(clock.now(), target);
+        assert_eq!(clock.slept.get(), Duration::from_secs(35 * 60));
+    }
+
+    /// Writes a byte at a time, giving other threads every chance to cut in.
+    struct Slow(Vec<u8>);
+
+    impl Write for Slow {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let Some(&byte) = buf.first() else {
+                return Ok(0);
+            };
+            self.0.push(byte);
+            thread::yield_now();
+
+            Ok(1)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// A reminder that only counts how many times it fires, without any output.
+    fn silent(options: Options) -> Reminder {
+        let mut reminder = Reminder::new(Delta::from_seconds(0), "Stand up");
+        reminder.options = Options {
+            notifiers: Vec::new(),
+            ..options
+        };
+        reminder
+    }
+
+    /// Keeps every message it's fired with.
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    impl Notifier for Recorder {
+        fn fire(&self, fired: &Fired) -> io::Result<()> {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("{} {}", fired.count, fired.labelled()));
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn fires_every_notifier() {
+        let clock = MockClock::new(chrono::Duration::zero());
+        let first = Arc::new(Recorder::default());
+        let second = Arc::new(Recorder::default());
+        let mut reminder = silent(Options {
+            count: Some(2),
+            label: Some("standup".to_string()),
+            ..Options::default()
+        });
+        reminder.options.notifiers = vec![first.clone(), second.clone()];
+
+        assert_eq!(reminder.trigger_with(&clock).unwrap(), 2);
+        for recorder in [first, second] {
+            assert_eq!(
+                *recorder.0.lock().unwrap(),
+                ["0 [standup] Stand up", "1 [standup] Stand up"]
+            );
+        }
+    }
+
+    #[test]
+    fn counts_down_remaining() {
+        let clock = MockClock::new(chrono::Duration::zero());
+        let recorder = Arc::new(Recorder::default());
+        let mut reminder = silent(Options {
+            count: Some(3),
+            ..Options::default()
+        });
+        reminder.message = "Stand up ({remaining} more)".to_string();
+        reminder.options.notifiers = vec![recorder.clone()];
+
+        reminder.trigger_with(&clock).unwrap();
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            [
+                "0 Stand up (2 more)",
+                "1 Stand up (1 more)",
+                "2 Stand up (0 more)"
+            ]
+        );
+
+        let recorder = Arc::new(Recorder::default());
+        reminder.options.count = None;
+        reminder.options.once = true;
+        reminder.options.notifiers = vec![recorder.clone()];
+        reminder.trigger_with(&clock).unwrap();
+        assert_eq!(*recorder.0.lock().unwrap(), ["0 Stand up (0 more)"]);
+
+        let recorder = Arc::new(Recorder::default());
+        reminder.options.once = false;
+        reminder.options.count = None;
+        reminder.options.until = Some(clock.now() + chrono::Duration::seconds(45));
+        reminder.options.notifiers = vec![recorder.clone()];
+        reminder.trigger_with(&clock).unwrap();
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            ["0 Stand up (∞ more)", "1 Stand up (∞ more)"]
+        );
+    }
+
+    #[test]
+    fn repeats_on_the_clock() {
+        let clock = MockClock::new(chrono::Duration::zero());
+        let reminder = silent(Options {
+            count: Some(3),
+            ..Options::default()
+        });
+        assert_eq!(reminder.trigger_with(&clock).unwrap(), 3);
+        assert_eq!(clock.slept.get(), Duration::from_secs(60));
+
+        // Repeats stop once the next one would be past `until`.
+        let clock = MockClock::new(chrono::Duration::zero());
+        let reminder = silent(Options {
+            until: Some(clock.now() + chrono::Duration::seconds(100)),
+            ..Options::default()
+        });
+        assert_eq!(reminder.trigger_with(&clock).unwrap(), 4);
+        assert_eq!(clock.slept.get(), Duration::from_secs(90));
+
+        let clock = MockClock::new(chrono::Duration::zero());
+        let reminder = silent(Options {
+            count: Some(4),
+            backoff: 2.0,
+            repeat_max: Some(Duration::from_secs(90)),
+            ..Options::default()
+        });
+        assert_eq!(reminder.trigger_with(&clock).unwrap(), 4);
+        assert_eq!(clock.slept.get(), Duration::from_secs(30 + 60 + 90));
+    }
+
+    #[test]
+    fn holds_off_while_dnd() {
+        let dnd = env::temp_dir().join(format!("remind-dnd-{}", process::id()));
+        fs::write(&dnd, "").unwrap();
+        let clock = MockClock::new(chrono::Duration::zero());
+        let mut reminder = silent(Options {
+            once: true,
+            until: Some(clock.now() + chrono::Duration::seconds(100)),
+            dnd_file: Some(dnd.clone()),
+            ..Options::default()
+        });
+
+        // Checked again every 30 seconds until it's past `until`, without ever firing.
+        assert_eq!(reminder.trigger_with(&clock).unwrap(), 0);
+        assert_eq!(clock.slept.get(), Duration::from_secs(120));
+
+        fs::remove_file(&dnd).unwrap();
+        reminder.options.until = None;
+        assert_eq!(reminder.trigger_with(&clock).unwrap(), 1);
+    }
+
+    #[test]
+    fn runs_queue_at_targets() {
+        let clock = MockClock::new(chrono::Duration::zero());
+        let first = clock.now() + chrono::Duration::minutes(10);
+        let second = clock.now() + chrono::Duration::minutes(20);
+        let mut queue = [
+            (
+                silent(Options {
+                    repeat: Duration::from_secs(240),
+                    until: Some(second),
+                    ..Options::default()
+                }),
+                first,
+            ),
+            (
+                silent(Options {
+                    once: true,
+                    ..Options::default()
+                }),
+                second,
+            ),
+        ];
+
+        let mut targets = Vec::new();
+        let fired = run_queue(&clock, &mut queue, |_, target| {
+            targets.push(target);
+            sleep_until(&clock, target)
+        })
+        .unwrap();
+
+        // The first one fires at 10, 14 and 18 minutes, stopping before the second is due.
+        assert_eq!(fired, 4);
+        assert_eq!(targets, [first, second]);
+        assert_eq!(clock.now(), second);
+    }
+
+    #[test]
+    fn checks_quiet_hours() {
+        let time = |s| NaiveTime::parse_from_str(s, "%H:%M").unwrap();
+        let night = QuietHours {
+            start: time("22:00"),
+            end: time("07:00"),
+        };
+        assert!(night.contains(time("22:00")));
+        assert!(night.contains(time("23:59")));
+        assert!(night.contains(time("00:00")));
+        assert!(night.contains(time("06:59")));
+        assert!(!night.contains(time("07:00")));
+        assert!(!night.contains(time("12:00")));
+        assert!(!night.contains(time("21:59")));
+
+        let lunch = QuietHours {
+            start: time("12:00"),
+            end: time("13:00"),
+        };
+        assert!(lunch.contains(time("12:30")));
+        assert!(!lunch.contains(time("13:00")));
+        assert!(!lunch.contains(time("00:00")));
+    }
+
+    #[test]
+    fn renders_placeholders() {
+        let set_at = Local.with_ymd_and_hms(2026, 3, 1, 14, 0, 0).unwrap();
+        let now = Local.with_ymd_and_hms(2026, 3, 1, 15, 30, 5).unwrap();
+
+        assert_eq!(
+            render("It's {time} on {date}, stand up", now, None),
+            "It's 15:30 on 2026-03-01, stand up"
+        );
+        assert_eq!(
+            render("Working for {elapsed}", now, Some(set_at)),
+            "Working for 1h30m5s"
+        );
+        assert_eq!(render("{elapsed}", now, None), "{elapsed}");
+        assert_eq!(
+            render("{unknown} {time", now, Some(set_at)),
+            "{unknown} {time"
+        );
+    }
+
+    #[test]
+    fn jitters_within_range() {
+        assert_eq!(jitter(90, 0), 90);
+        for _ in 0..100 {
+            assert!((60..=120).contains(&jitter(90, 30)));
+            assert!((1..=40).contains(&jitter(10, 30)));
+        }
+        assert_eq!(jitter(0, 0), 1);
+        assert_eq!(jitter(u64::MAX, 0), u64::MAX);
+    }
+
+    #[test]
+    fn emits_whole_triggers() {
+        let out = Mutex::new(Slow(Vec::new()));
+        thread::scope(|scope| {
+            for message in ["Stand up", "Drink water"] {
+                let out = &out;
+                scope.spawn(move || {
+                    for _ in 0..50 {
+                        emit(out, &["\x07", message, "\n"]).unwrap();
+                    }
+                });
+            }
+        });
+
+        let output = String::from_utf8(out.into_inner().unwrap().0).unwrap();
+        assert_eq!(output.lines().count(), 100);
+        for line in output.lines() {
+            assert!(
+                line == "\x07Stand up" || line == "\x07Drink water",
+                "interleaved line {:?}",
+                line
+            );
+        }
+    }
+}
@@ -1,112 +1,1371 @@
 use std::{
-    error::Error,
-    fmt::Display,
-    io::{self, Write},
-    str::FromStr,
+    env,
+    fs::{self, OpenOptions},
+    io::{self, IsTerminal, Write},
+    path::{Path, PathBuf},
+    process::{self, ExitCode},
+    sync::Arc,
     thread,
     time::Duration,
 };
 
-use clap::Parser;
+use chrono::{DateTime, Local, NaiveTime};
+use clap::{error::ErrorKind, CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use remind::{
+    storage::{self, Saved},
+    Clear, Delta, DeltaError, DeltaErrorKind, Desktop, Exec, Format, Log, Notifier, Options,
+    QuietHours, Reminder, Sound, SystemClock, Terminal, Urgency,
+};
+use serde_json::json;
+use tracing::Level;
+use tracing_subscriber::{filter::Targets, layer::SubscriberExt, util::SubscriberInitExt};
+
+use config::Config;
+use heartbeat::Heartbeat;
+
+mod config;
+mod heartbeat;
+mod tui;
+
+/// Exit code for runtime errors, like failing to read or write saved reminders.
+const EXIT_IO: u8 = 1;
+/// Exit code for invalid arguments, like a malformed delta (also used by clap).
+const EXIT_USAGE: u8 = 2;
+/// Exit code for a reminder cancelled with Ctrl-C.
+const EXIT_CANCELLED: u8 = 130;
+
+/// Deltas longer than this are likely a typo, so they need to be confirmed.
+const MAX_SECONDS: u64 = 30 * 86400;
+
+/// Shown after the options in --help: the exit codes, placeholders, signals, config file and
+/// environment variables.
+const AFTER_HELP: &str = "Exit codes:
+  0    Reminder completed
+  1    Runtime error (ex: saved reminders can't be read or written)
+  2    Invalid arguments (ex: malformed delta)
+  130  Reminder cancelled with Ctrl-C
+
+Messages can include {time}, {date}, {elapsed} and {remaining}, the
+reminders left with --count (∞ without it), filled in when the reminder
+triggers (ex: \"It's {time}, stand up\" or \"Stand up ({remaining} more)\").
+
+On Unix, send SIGUSR1 to pause the countdown and SIGUSR2 to resume it.
+
+Defaults for --once, --repeat, --sound and the message can be set in
+<config dir>/glutools/remind.toml (ex: ~/.config/glutools/remind.toml):
+  once = false
+  repeat = \"5m\"
+  sound = \"/path/to/alarm.wav\"
+  message = \"Time is up!\"
+
+When left out, the delta is read from REMIND_DEFAULT_DELTA and the message
+from stdin when piped, then REMIND_DEFAULT_MESSAGE, then the config file.
+Command line arguments win over all of these.";
+
+/// When to print in color
+#[derive(Clone, Copy, ValueEnum)]
+enum Color {
+    /// Only when printing to a terminal and NO_COLOR isn't set
+    Auto,
+    /// Even when piped
+    Always,
+    /// Plain text only
+    Never,
+}
 
 /// Simple remind tool
 #[derive(Parser)]
-#[command(version)]
+#[command(
+    version,
+    after_help = AFTER_HELP,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[command(flatten)]
+    args: Args,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Schedule a reminder, same as leaving the subcommand out (ex: remind add 2h "Go for a walk")
+    Add(Box<Args>),
+    /// List the saved reminders with the time left until each one
+    List {
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// Remove every saved reminder
+    Clear,
+    /// Check a delta without scheduling anything, printing its canonical form and total seconds
+    Check {
+        /// Delta to check (ex: "2 hours 30 minutes")
+        delta: String,
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+}
+
+#[derive(clap::Args)]
 struct Args {
     /// Run reminder only once
     #[arg(short, long)]
     once: bool,
-    /// Time to wait before the reminder triggers (ex: 2h30m)
-    delta: Delta,
-    /// Optional reminder message (ex: "Go for a walk")
+    /// Stop after reminding this many times
+    #[arg(short, long, conflicts_with = "once", value_parser = clap::value_parser!(u64).range(1..))]
+    count: Option<u64>,
+    /// Also show a desktop notification when the reminder triggers
+    #[arg(short, long)]
+    notify: bool,
+    /// Urgency of the desktop notification, critical also rings the bell three times as often
+    #[arg(short, long, value_enum, default_value_t = Urgency::Normal)]
+    urgency: Urgency,
+    /// Print the reminder without ringing the terminal bell
+    #[arg(long)]
+    no_bell: bool,
+    /// Ring the terminal bell this many times per reminder, from 0 to 10
+    #[arg(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u8).range(0..=10))]
+    bell_count: u8,
+    /// Only print reminders during these hours, without a bell, sound or desktop notification,
+    /// alerting fully again outside of them (ex: 22:00-07:00)
+    #[arg(long, value_name = "HH:MM-HH:MM", value_parser = parse_quiet_hours)]
+    quiet_hours: Option<QuietHours>,
+    /// Hold off on firing while this file exists, checking again after every repeat interval,
+    /// so touch and rm toggle do not disturb for every running reminder
+    /// [default: ~/.local/share/glutools/remind.dnd]
+    #[arg(long, value_name = "PATH")]
+    dnd_file: Option<PathBuf>,
+    /// Play an audio file instead of the terminal bell (ex: alarm.wav), only in builds with the
+    /// sound feature (cargo install --features sound), ringing the bell otherwise
+    #[arg(long, value_name = "PATH")]
+    sound: Option<PathBuf>,
+    /// Play a different audio file on every repeat after the first (ex: nudge.wav), also needing
+    /// the sound feature
+    #[arg(long, value_name = "PATH")]
+    repeat_sound: Option<PathBuf>,
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+    /// What to clear before printing the first reminder, none when not printing to a terminal
+    #[arg(long, value_enum, default_value_t = Clear::Screen)]
+    clear: Clear,
+    /// Print the reminder in color
+    #[arg(long, value_enum, value_name = "WHEN", default_value_t = Color::Auto)]
+    color: Color,
+    /// Time between repeated reminders, 30s by default (ex: 5m)
+    #[arg(short, long, value_name = "DELTA", value_parser = Delta::parse)]
+    repeat: Option<Delta>,
+    /// Remind every time this interval passes, waiting it before the first reminder too (ex: 25m)
+    #[arg(long, value_name = "DELTA", value_parser = Delta::parse, conflicts_with_all = ["once", "repeat", "repeat_backoff", "at", "hours", "minutes", "seconds", "resume", "schedule", "list"])]
+    every: Option<Delta>,
+    /// Multiply the time between repeated reminders by this after each one (ex: 2)
+    #[arg(long, value_name = "FACTOR", value_parser = parse_factor, conflicts_with = "once")]
+    repeat_backoff: Option<f64>,
+    /// Wait a random offset up to this much shorter or longer, so the reminder isn't predictable (ex: 10m)
+    #[arg(long, value_name = "DELTA", value_parser = Delta::parse)]
+    jitter: Option<Delta>,
+    /// Also apply --jitter to every repeat
+    #[arg(long, requires = "jitter", conflicts_with = "once")]
+    jitter_repeat: bool,
+    /// Longest time between repeated reminders with --repeat-backoff, no limit by default (ex: 1h)
+    #[arg(long, value_name = "DELTA", value_parser = Delta::parse, requires = "repeat_backoff")]
+    repeat_max: Option<Delta>,
+    /// Print a quieter heads-up this long before the reminder, without a bell or notification
+    /// (ex: 5m)
+    #[arg(long, value_name = "DELTA", value_parser = Delta::parse, conflicts_with_all = ["resume", "schedule", "list", "tui"])]
+    warn_before: Option<Delta>,
+    /// Stop repeating at the next occurrence of a clock time (ex: 18:30)
+    #[arg(long, value_name = "HH:MM", value_parser = parse_time, conflicts_with = "once")]
+    repeat_until: Option<NaiveTime>,
+    /// Rewrite --heartbeat-file with the current time this often while running, for a monitor (ex: 1m)
+    #[arg(long, value_name = "DELTA", value_parser = Delta::parse, requires = "heartbeat_file", conflicts_with_all = ["dry_run", "list"])]
+    heartbeat: Option<Delta>,
+    /// File written by --heartbeat, removed once every reminder is done (ex: remind.heartbeat)
+    #[arg(long, value_name = "PATH", requires = "heartbeat")]
+    heartbeat_file: Option<PathBuf>,
+    /// Keep repeating until Enter is pressed, needs stdin to be a terminal
+    #[arg(long, conflicts_with_all = ["once", "background"])]
+    until_ack: bool,
+    /// Press s to snooze repeated reminders for this long, or q to quit (ex: 10m)
+    #[arg(long, value_name = "DELTA", value_parser = Delta::parse)]
+    snooze: Option<Delta>,
+    /// Count the delta from this long ago, triggering right away if it already passed (ex: 5m)
+    #[arg(long, value_name = "DELTA", value_parser = Delta::parse, conflicts_with_all = ["at", "resume"])]
+    since: Option<Delta>,
+    /// Count the delta from a timestamp instead of now, triggering right away if it already passed
+    /// (ex: 2026-03-01T14:00:00+01:00)
+    #[arg(long, value_name = "RFC3339", value_parser = parse_timestamp, conflicts_with_all = ["at", "since", "resume"])]
+    relative_to: Option<DateTime<Local>>,
+    /// Trigger the reminder at the next occurrence of a clock time instead (ex: 18:30)
+    ///
+    /// The clock is checked every second while waiting, so changing it or suspending the
+    /// machine doesn't make the reminder late.
+    #[arg(long, value_name = "HH:MM", value_parser = parse_time)]
+    at: Option<NaiveTime>,
+    /// Wait this many hours instead of giving a delta, adding up with --minutes and --seconds
+    #[arg(long, value_name = "N", conflicts_with_all = ["at", "resume", "schedule", "list"])]
+    hours: Option<u64>,
+    /// Wait this many minutes instead of giving a delta (ex: 90)
+    #[arg(long, value_name = "N", conflicts_with_all = ["at", "resume", "schedule", "list"])]
+    minutes: Option<u64>,
+    /// Wait this many seconds instead of giving a delta (ex: 5400)
+    #[arg(long, value_name = "N", conflicts_with_all = ["at", "resume", "schedule", "list"])]
+    seconds: Option<u64>,
+    /// Append every triggered reminder to a file, with a timestamp (ex: remind.log)
+    #[arg(long, value_name = "PATH")]
+    log: Option<PathBuf>,
+    /// Run a shell command every time the reminder triggers (ex: "mpv alarm.mp3")
+    #[arg(long, value_name = "CMD")]
+    exec: Option<String>,
+    /// Run the --exec command again up to this many times when it fails
+    #[arg(long, value_name = "N", default_value_t = 0, requires = "exec")]
+    exec_retries: u32,
+    /// Time to wait before running a failed --exec command again
+    #[arg(long, value_name = "DELTA", value_parser = Delta::parse, default_value = "1s", requires = "exec")]
+    exec_retry_delay: Delta,
+    /// Show the output of a shell command run when the reminder triggers as the message
+    /// instead, falling back to the message if it fails (ex: "cat standup.txt")
+    #[arg(long, value_name = "CMD")]
+    message_cmd: Option<String>,
+    /// Only run the --exec command, without printing the reminder
+    #[arg(long, requires = "exec")]
+    no_print: bool,
+    /// Name shown in front of the reminder output (ex: standup)
+    #[arg(short, long)]
+    label: Option<String>,
+    /// Don't print when the reminder will trigger, only the reminder itself
+    #[arg(short, long)]
+    quiet: bool,
+    /// Also print the current time, trigger time and total seconds to stderr, and how long ago
+    /// the reminder was set once it triggers
+    #[arg(short, long)]
+    verbose: bool,
+    /// Print developer diagnostics to stderr, like the parsed deltas, every sleep and trigger
+    #[arg(long)]
+    debug: bool,
+    /// Count down full screen with big digits while waiting, press p to pause or q to quit
+    #[arg(long, conflicts_with_all = ["progress", "background", "dry_run", "resume"])]
+    tui: bool,
+    /// Count down the time left on a single line while waiting
+    #[arg(short, long)]
+    progress: bool,
+    /// Count up the time elapsed instead with --progress, like a stopwatch that rings at the delta
+    #[arg(long, requires = "progress", conflicts_with = "tui")]
+    elapsed: bool,
+    /// Only report when the reminder would trigger, without waiting for it
+    #[arg(long, conflicts_with = "resume")]
+    dry_run: bool,
+    /// Detach from the terminal and wait in the background, printing the PID (Unix only)
+    #[arg(long, conflicts_with = "dry_run")]
+    background: bool,
+    /// Save the reminder so it can be resumed after a reboot
+    #[arg(short, long)]
+    save: bool,
+    /// Refuse deltas longer than 30 days instead of asking to confirm them
+    #[arg(long)]
+    strict: bool,
+    /// Wait for the saved reminders instead, firing overdue ones right away
+    #[arg(long, conflicts_with_all = ["at", "delta", "save"])]
+    resume: bool,
+    /// Run every reminder listed in a file, one "<delta> <message>" per line (ex: reminders.txt)
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["at", "delta", "save", "resume", "dry_run", "tui", "since", "relative_to", "jitter", "message_file"])]
+    schedule: Option<PathBuf>,
+    /// List the saved reminders with the time left until each one, then exit
+    #[arg(long, conflicts_with_all = ["at", "delta", "save", "resume", "dry_run", "background"])]
+    list: bool,
+    /// Print a completion script for a shell and exit
+    #[arg(long, value_name = "SHELL", hide = true, exclusive = true)]
+    generate_completions: Option<Shell>,
+    /// Time to wait before the reminder triggers, +90 being 90 minutes (ex: 2h30m, 1.5h, 45s, 2d, 1w,
+    /// PT2H30M, "2 hours 30 minutes")
+    delta: Option<String>,
+    /// Reminder message, repeat to show a different one in turn on every repeat (ex: "Stand up")
+    #[arg(short, long = "message", value_name = "MESSAGE", conflicts_with_all = ["message", "more", "message_file", "schedule", "resume"])]
+    messages: Vec<String>,
+    /// Read the reminder message from a file instead, keeping every line (ex: notes.txt)
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["more", "resume"])]
+    message_file: Option<PathBuf>,
+    /// Optional reminder message, read from stdin when piped (ex: "Go for a walk")
     message: Option<String>,
+    /// More reminders to queue, as pairs of delta and message (ex: 1h "Lunch")
+    #[arg(value_name = "DELTA MESSAGE")]
+    more: Vec<String>,
 }
 
-#[derive(Clone)]
-struct Delta {
-    hours: u8,
-    minutes: u8,
+/// Prints a clap error and exits, with EXIT_USAGE unless it's just help or version output.
+fn exit_with(error: clap::Error) -> ! {
+    let _ = error.print();
+    let code = match error.use_stderr() {
+        true => EXIT_USAGE,
+        false => 0,
+    };
+
+    process::exit(code.into())
 }
 
-impl FromStr for Delta {
-    type Err = DeltaError;
+fn parse_time(s: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(s, "%H:%M").map_err(|_| "Invalid time, ex: 18:30".to_string())
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut hours = 0;
-        let mut minutes = 0;
-        let mut number = String::new();
+fn parse_quiet_hours(s: &str) -> Result<QuietHours, String> {
+    let invalid = || "Invalid quiet hours, ex: 22:00-07:00".to_string();
+    let (start, end) = s.split_once('-').ok_or_else(invalid)?;
+    let quiet = QuietHours {
+        start: parse_time(start.trim()).map_err(|_| invalid())?,
+        end: parse_time(end.trim()).map_err(|_| invalid())?,
+    };
 
-        for c in s.chars() {
-            match c.to_string().as_str() {
-                "h" => {
-                    hours = number
-                        .parse()
-                        .map_err(|_| DeltaError::new("Invalid hours"))?;
-                    number.clear();
-                }
-                "m" => {
-                    minutes = number
-                        .parse()
-                        .map_err(|_| DeltaError::new("Invalid minutes"))?;
-                    number.clear();
-                }
-                _ => {
-                    if c.is_numeric() {
-                        number = format!("{}{}", number, c)
-                    } else {
-                        return Err(DeltaError::new("Invalid syntax, ex: 2h30m"));
-                    }
-                }
+    match quiet.start == quiet.end {
+        true => Err("Quiet hours must start and end at different times".to_string()),
+        false => Ok(quiet),
+    }
+}
+
+fn parse_timestamp(s: &str) -> Result<DateTime<Local>, String> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|timestamp| timestamp.with_timezone(&Local))
+        .map_err(|_| "Invalid timestamp, ex: 2026-03-01T14:00:00+01:00".to_string())
+}
+
+fn parse_factor(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(factor) if factor.is_finite() && factor > 0.0 => Ok(factor),
+        _ => Err("Factor must be a positive number, ex: 2 or 0.5".to_string()),
+    }
+}
+
+/// Builds the reminder options from the command line flags.
+fn options(args: &Args) -> io::Result<Options> {
+    // --every repeats at the same interval it first waits for.
+    let (flag, repeat) = match &args.every {
+        Some(every) => ("--every", Some(every)),
+        None => ("--repeat", args.repeat.as_ref()),
+    };
+    let repeat = match repeat {
+        Some(_) if args.once => {
+            eprintln!("Ignoring --repeat since --once is set.");
+            Options::default().repeat
+        }
+        Some(repeat) => match repeat.total_seconds() {
+            0 => exit_with(Cli::command().error(
+                ErrorKind::ValueValidation,
+                format!(
+                    "invalid value for '{} <DELTA>': Repeat interval must not be zero",
+                    flag
+                ),
+            )),
+            seconds => match repeat.after(Local::now()) {
+                Ok(_) => Duration::from_secs(seconds),
+                Err(e) => exit_with(Cli::command().error(
+                    ErrorKind::ValueValidation,
+                    format!("invalid value '{}' for '{} <DELTA>': {}", repeat, flag, e),
+                )),
+            },
+        },
+        None => Options::default().repeat,
+    };
+
+    // Load the sounds and open the log up front, so a bad path is reported before waiting.
+    for path in args.sound.iter().chain(&args.repeat_sound) {
+        remind::check_sound(path)?;
+    }
+
+    let color = match args.color {
+        Color::Auto => {
+            io::stdout().is_terminal()
+                && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+        }
+        Color::Always => true,
+        Color::Never => false,
+    };
+    let bell = remind::bell(bells(args), args.urgency);
+    let repeat_sound = args.repeat_sound.clone().or(args.sound.clone());
+
+    // Outputs fire in this order, the sound last since it blocks until it's done playing.
+    let mut notifiers: Vec<Arc<dyn Notifier>> = Vec::new();
+    if !args.no_print {
+        // Clearing a file or pipe only leaves escape codes in it.
+        let clear = match io::stdout().is_terminal() {
+            true => args.clear,
+            false => Clear::None,
+        };
+        notifiers.push(Arc::new(Terminal::new(
+            args.format,
+            clear,
+            color,
+            match args.sound {
+                Some(_) => String::new(),
+                None => bell.clone(),
+            },
+            match repeat_sound {
+                Some(_) => String::new(),
+                None => bell.clone(),
+            },
+        )));
+    }
+    if let Some(path) = &args.log {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| {
+                io::Error::new(
+                    e.kind(),
+                    format!("Could not open log {}: {}", path.display(), e),
+                )
+            })?;
+        notifiers.push(Arc::new(Log::new(file)));
+    }
+    if let Some(command) = &args.exec {
+        notifiers.push(Arc::new(Exec::new(
+            command,
+            args.exec_retries,
+            Duration::from_secs(args.exec_retry_delay.total_seconds()),
+        )));
+    }
+    if args.notify {
+        notifiers.push(Arc::new(Desktop::new(args.urgency)));
+    }
+    if args.sound.is_some() || repeat_sound.is_some() {
+        // Only bells in text, so the other formats keep to one line per reminder.
+        let fallback = match args.format {
+            Format::Text => bell,
+            _ => String::new(),
+        };
+        notifiers.push(Arc::new(Sound::new(
+            args.sound.clone(),
+            repeat_sound,
+            fallback,
+        )));
+    }
+
+    Ok(Options {
+        once: args.once,
+        count: args.count,
+        repeat,
+        backoff: args.repeat_backoff.unwrap_or(1.0),
+        repeat_max: args
+            .repeat_max
+            .as_ref()
+            .map(|max| Duration::from_secs(max.total_seconds())),
+        jitter: args
+            .jitter
+            .as_ref()
+            .filter(|_| args.jitter_repeat)
+            .map(|jitter| Duration::from_secs(jitter.total_seconds())),
+        until: args
+            .repeat_until
+            .map(|time| Local::now() + Duration::from_secs(Delta::until(time).total_seconds())),
+        snooze: args
+            .snooze
+            .as_ref()
+            .map(|snooze| Duration::from_secs(snooze.total_seconds())),
+        until_ack: args.until_ack,
+        notifiers,
+        quiet_hours: args.quiet_hours,
+        dnd_file: match &args.dnd_file {
+            Some(path) => Some(path.clone()),
+            None => storage::dnd_path().ok(),
+        },
+        label: args.label.clone(),
+        messages: args.messages.clone(),
+        message_cmd: args.message_cmd.clone(),
+        color,
+        set_at: Some(Local::now()),
+        show_elapsed: args.verbose,
+        format: args.format,
+    })
+}
+
+/// How many times the bell rings per reminder, none with --no-bell.
+fn bells(args: &Args) -> u8 {
+    match args.no_bell {
+        true => 0,
+        false => args.bell_count,
+    }
+}
+
+/// Waits for every saved reminder in its own thread, firing overdue ones right away.
+fn resume(options: &Options, quiet: bool) -> io::Result<u64> {
+    let saved = storage::load()?;
+    match options.format {
+        _ if quiet => {}
+        Format::Text => println!("Resuming {} reminder(s).", saved.len()),
+        Format::Json => println!("{}", json!({ "event": "resume", "reminders": saved.len() })),
+        Format::PlainLine => {}
+    }
+
+    in_threads(&saved, |saved| {
+        let seconds = (saved.target - Local::now()).num_seconds().max(0);
+        let mut reminder =
+            Reminder::new(Delta::from_seconds(seconds.unsigned_abs()), &saved.message);
+        reminder.options = options.clone();
+
+        remind::sleep(reminder.duration())?;
+        storage::remove(saved)?;
+        reminder.trigger()
+    })
+}
+
+/// Reads every reminder of a schedule file, each line being a delta and a message, exiting with a
+/// usage error on the first invalid delta.
+fn read_schedule(
+    path: &Path,
+    options: &Options,
+    default: &str,
+    strict: bool,
+) -> io::Result<Vec<Reminder>> {
+    let contents = fs::read_to_string(path).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!("Could not read schedule {}: {}", path.display(), e),
+        )
+    })?;
+
+    let mut reminders = Vec::new();
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (delta, message) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let delta = Delta::parse(delta)
+            .and_then(|delta| delta.after(Local::now()).map(|_| delta))
+            .unwrap_or_else(|e| {
+                exit_with(Cli::command().error(
+                    ErrorKind::ValueValidation,
+                    format!(
+                        "invalid delta '{}' on line {} of {}: {}",
+                        delta,
+                        number + 1,
+                        path.display(),
+                        e
+                    ),
+                ))
+            });
+        check_length(&delta, strict, true)?;
+        let message = match message.trim() {
+            "" => default,
+            message => message,
+        };
+        let mut reminder = Reminder::new(delta, message);
+        reminder.options = options.clone();
+        reminders.push(reminder);
+    }
+
+    Ok(reminders)
+}
+
+/// Runs every reminder of a schedule in its own thread.
+fn schedule(reminders: &[Reminder], quiet: bool) -> io::Result<u64> {
+    if !quiet {
+        for reminder in reminders {
+            announce(&reminder.delta, &reminder.options);
+        }
+    }
+
+    in_threads(reminders, Reminder::run)
+}
+
+/// Calls `f` on every item in its own thread, adding up how many times they fired.
+///
+/// Returns the first error instead, once they're all done.
+fn in_threads<T: Sync>(items: &[T], f: impl Fn(&T) -> io::Result<u64> + Sync) -> io::Result<u64> {
+    thread::scope(|scope| {
+        let handles: Vec<_> = items.iter().map(|item| scope.spawn(|| f(item))).collect();
+
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|e| std::panic::resume_unwind(e))
+            })
+            .sum()
+    })
+}
+
+/// Removes every saved reminder.
+fn clear() -> io::Result<()> {
+    let removed = storage::clear()?;
+    println!("Removed {} saved reminder(s).", removed);
+
+    Ok(())
+}
+
+/// Validates a delta, printing its canonical form and total seconds or why it's invalid.
+fn check(delta: &str, format: Format) -> ExitCode {
+    let delta = match try_parse_delta(delta) {
+        Ok(delta) => delta,
+        Err(e) => {
+            eprintln!("Error: invalid delta '{}': {}", delta, e);
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+
+    let seconds = delta.total_seconds();
+    match format {
+        Format::Text => println!("{} is {} second(s).", delta, seconds),
+        Format::Json => println!(
+            "{}",
+            json!({ "event": "check", "canonical": delta.to_string(), "seconds": seconds, "delta": delta })
+        ),
+        Format::PlainLine => println!("{}\t{}", delta, seconds),
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Prints every saved reminder with the time left until it triggers, marking the overdue ones.
+fn list(format: Format) -> io::Result<()> {
+    let saved = storage::load()?;
+    if saved.is_empty() && format == Format::Text {
+        println!("No saved reminders.");
+    }
+
+    let now = Local::now();
+    for saved in &saved {
+        let seconds = (saved.target - now).num_seconds();
+        let left = Delta::from_seconds(seconds.unsigned_abs());
+        match format {
+            Format::Text if seconds < 0 => println!("Overdue by {}: {}", left, saved.message),
+            Format::Text => println!("In {}: {}", left, saved.message),
+            Format::Json => println!(
+                "{}",
+                json!({
+                    "event": "saved",
+                    "target": saved.target.to_rfc3339(),
+                    "seconds": seconds.max(0),
+                    "overdue": seconds < 0,
+                    "message": saved.message,
+                })
+            ),
+            Format::PlainLine => println!("{}\t{}", saved.target.to_rfc3339(), saved.message),
+        }
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let config = Config::load();
+    let cli = Cli::try_parse().unwrap_or_else(|e| exit_with(e));
+    let args = match cli.command {
+        None => cli.args,
+        Some(Command::Add(args)) => *args,
+        Some(Command::List { format }) => return exit_code(list(format)),
+        Some(Command::Clear) => return exit_code(clear()),
+        Some(Command::Check { delta, format }) => return check(&delta, format),
+    };
+    if let Some(shell) = args.generate_completions {
+        clap_complete::generate(shell, &mut Cli::command(), "remind", &mut io::stdout());
+        return ExitCode::SUCCESS;
+    }
+    if args.debug {
+        // Only this crate's events, leaving out whatever the dependencies log.
+        tracing_subscriber::fmt()
+            .with_writer(io::stderr)
+            .with_max_level(Level::DEBUG)
+            .finish()
+            .with(Targets::new().with_target("remind", Level::DEBUG))
+            .init();
+    }
+
+    exit_code(config.and_then(|config| run(args, config)))
+}
+
+/// Forks into the background with --background, then installs the signal handlers.
+///
+/// They're installed this late so Ctrl-C still interrupts the prompts before waiting, and so a
+/// forked child has the signal threads, which it doesn't keep from its parent.
+fn prepare_wait(background: bool, format: Format) -> io::Result<()> {
+    if background {
+        self::background(format)?;
+    }
+
+    handle_signals()
+}
+
+/// Cancels on Ctrl-C, and pauses and resumes on SIGUSR1 and SIGUSR2 on Unix.
+fn handle_signals() -> io::Result<()> {
+    ctrlc::set_handler(remind::cancel).map_err(io::Error::other)?;
+    #[cfg(unix)]
+    handle_pause_signals()?;
+
+    Ok(())
+}
+
+/// Reports how running went, mapping errors to the exit codes.
+fn exit_code(result: io::Result<()>) -> ExitCode {
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) if e.kind() == io::ErrorKind::Interrupted => {
+            eprintln!("Reminder cancelled.");
+            ExitCode::from(EXIT_CANCELLED)
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::from(EXIT_IO)
+        }
+    }
+}
+
+/// Pauses every countdown on SIGUSR1 and resumes it on SIGUSR2.
+#[cfg(unix)]
+fn handle_pause_signals() -> io::Result<()> {
+    use signal_hook::{
+        consts::{SIGUSR1, SIGUSR2},
+        iterator::Signals,
+    };
+
+    let mut signals = Signals::new([SIGUSR1, SIGUSR2])?;
+    thread::spawn(move || {
+        for signal in signals.forever() {
+            remind::set_paused(signal == SIGUSR1);
+        }
+    });
+
+    Ok(())
+}
+
+/// Forks into a new session, returning in the child while the parent prints its PID and exits.
+#[cfg(unix)]
+fn background(format: Format) -> io::Result<()> {
+    use nix::unistd::{self, ForkResult};
+
+    // SAFETY: the child only keeps this thread, and no other thread has been started yet since
+    // the signal handlers are only installed after forking.
+    match unsafe { unistd::fork() }.map_err(io::Error::from)? {
+        ForkResult::Parent { child } => {
+            match format {
+                Format::Text => println!("Running in the background as PID {}.", child),
+                Format::Json => println!(
+                    "{}",
+                    json!({ "event": "background", "pid": child.as_raw() })
+                ),
+                Format::PlainLine => println!("{}", child),
             }
+            process::exit(0)
         }
+        ForkResult::Child => {
+            unistd::setsid().map_err(io::Error::from)?;
+
+            Ok(())
+        }
+    }
+}
 
-        Ok(Self { hours, minutes })
+#[cfg(not(unix))]
+fn background(_format: Format) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "--background is only supported on Unix",
+    ))
+}
+
+/// Reads an environment variable holding a default, ignoring it when empty.
+fn env_default(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|value| !value.trim().is_empty())
+}
+
+/// The delta given on the command line, or REMIND_DEFAULT_DELTA.
+fn default_delta(arg: Option<&str>) -> Option<String> {
+    arg.map(str::to_string)
+        .or_else(|| env_default("REMIND_DEFAULT_DELTA"))
+}
+
+/// Adds up --hours, --minutes and --seconds into a delta, or None when none of them is given.
+fn numeric_delta(
+    hours: Option<u64>,
+    minutes: Option<u64>,
+    seconds: Option<u64>,
+) -> Option<Result<Delta, DeltaError>> {
+    if hours.is_none() && minutes.is_none() && seconds.is_none() {
+        return None;
     }
+
+    let total = hours
+        .unwrap_or(0)
+        .checked_mul(3600)
+        .zip(minutes.unwrap_or(0).checked_mul(60))
+        .and_then(|(hours, minutes)| hours.checked_add(minutes))
+        .and_then(|total| total.checked_add(seconds.unwrap_or(0)));
+
+    Some(
+        total
+            .map(Delta::from_seconds)
+            .ok_or_else(|| DeltaError::new(DeltaErrorKind::Overflow, "Duration too large"))
+            .and_then(|delta| delta.after(Local::now()).map(|_| delta)),
+    )
+}
+
+/// Message used when none is given, from REMIND_DEFAULT_MESSAGE, the config file or built in.
+fn default_message(config: Option<&str>) -> String {
+    env_default("REMIND_DEFAULT_MESSAGE")
+        .or_else(|| config.map(str::to_string))
+        .unwrap_or_else(|| "Time is up!".to_string())
+}
+
+/// Parses the compact form, falling back to plain English like "2 hours 30 minutes".
+fn try_parse_delta(s: &str) -> Result<Delta, DeltaError> {
+    // Report the plain English error when the input is written with words, unless it's ISO-8601
+    // or the +90 shortcut.
+    let words = !s.trim_start().starts_with(['P', 'p', '+'])
+        && s.split(|c: char| !c.is_alphabetic())
+            .any(|word| word.chars().count() > 1);
+
+    Delta::parse(s)
+        .or_else(|e| Delta::from_natural(s).map_err(|natural| if words { natural } else { e }))
+        .and_then(|delta| delta.after(Local::now()).map(|_| delta))
+}
+
+/// Parses a delta like `try_parse_delta`, exiting with a usage error when it's invalid.
+fn parse_delta(s: &str) -> Delta {
+    try_parse_delta(s).unwrap_or_else(|e| {
+        exit_with(Cli::command().error(
+            ErrorKind::ValueValidation,
+            format!("invalid value '{}' for '<DELTA>': {}", s, e),
+        ))
+    })
 }
 
-#[derive(Debug)]
-struct DeltaError {
-    message: String,
+/// Makes sure a delta longer than MAX_SECONDS is intended, asking to confirm it on a terminal.
+fn check_length(delta: &Delta, strict: bool, ask: bool) -> io::Result<()> {
+    if delta.total_seconds() <= MAX_SECONDS {
+        return Ok(());
+    }
+    if strict {
+        exit_with(Cli::command().error(
+            ErrorKind::ValueValidation,
+            format!(
+                "invalid value '{}' for '<DELTA>': Delta is longer than 30 days",
+                delta
+            ),
+        ));
+    }
+
+    eprintln!("Warning: {} is longer than 30 days.", delta);
+    if !ask || !io::stdin().is_terminal() {
+        return Ok(());
+    }
+    eprint!("Continue anyway? [y/N] ");
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+
+    match answer.trim().to_lowercase().as_str() {
+        "y" | "yes" => Ok(()),
+        _ => Err(io::Error::new(
+            io::ErrorKind::Interrupted,
+            "Reminder cancelled",
+        )),
+    }
 }
 
-impl DeltaError {
-    fn new(message: &str) -> Self {
-        Self {
-            message: message.to_string(),
+/// Message of the reminder with its label in front, ex: [standup] Time is up!
+fn labelled(reminder: &Reminder) -> String {
+    match &reminder.options.label {
+        Some(label) => format!("[{}] {}", label, reminder.message),
+        None => reminder.message.clone(),
+    }
+}
+
+/// Prints how long until the reminder triggers.
+fn announce(delta: &Delta, options: &Options) {
+    match (options.format, &options.label) {
+        (Format::Text, Some(label)) => println!(
+            "[{}] Remind in {}.",
+            label,
+            remind::highlight(&delta.to_string(), options.color)
+        ),
+        (Format::Text, None) => println!(
+            "Remind in {}.",
+            remind::highlight(&delta.to_string(), options.color)
+        ),
+        (Format::Json, label) => {
+            let mut event =
+                json!({ "event": "start", "seconds": delta.total_seconds(), "delta": delta });
+            if let Some(label) = label {
+                event["label"] = json!(label);
+            }
+            println!("{}", event);
         }
+        (Format::PlainLine, _) => {}
+    }
+}
+
+/// Runs `f` while writing the --heartbeat file, removing it once `f` succeeds.
+fn with_heartbeat<T>(
+    every: &Option<Delta>,
+    path: &Option<PathBuf>,
+    f: impl FnOnce() -> io::Result<T>,
+) -> io::Result<T> {
+    let heartbeat = match (every, path) {
+        (Some(every), Some(path)) => Some(Heartbeat::start(
+            path,
+            Duration::from_secs(every.total_seconds()),
+        )?),
+        _ => None,
+    };
+
+    let result = f()?;
+    if let Some(heartbeat) = heartbeat {
+        heartbeat.stop()?;
     }
+
+    Ok(result)
 }
 
-impl Display for DeltaError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.message)
+/// Prints how many times the reminders fired since `start`, once they're done.
+fn summary(fired: u64, start: DateTime<Local>, format: Format) {
+    let seconds = (Local::now() - start).num_seconds().max(0).unsigned_abs();
+    match format {
+        Format::Text => println!(
+            "Fired {} time(s) over {}.",
+            fired,
+            Delta::from_seconds(seconds)
+        ),
+        Format::Json => println!(
+            "{}",
+            json!({ "event": "summary", "fired": fired, "seconds": seconds })
+        ),
+        Format::PlainLine => {}
     }
 }
 
-impl Error for DeltaError {}
+/// Prints the heads-up given `warn` before the reminder triggers, ex: 5m until: Standup
+fn warning(reminder: &Reminder, warn: &Delta, options: &Options) {
+    let now = Local::now();
+    let message = reminder.first_message(now);
+    match options.format {
+        Format::Text => {
+            let text = match &options.label {
+                Some(label) => format!("{} until: [{}] {}", warn, label, message),
+                None => format!("{} until: {}", warn, message),
+            };
+            match options.color {
+                true => println!("\x1b[33m{}\x1b[0m", text),
+                false => println!("{}", text),
+            }
+        }
+        Format::Json => {
+            let mut event = json!({
+                "event": "warning",
+                "timestamp": now.to_rfc3339(),
+                "message": message,
+                "seconds": warn.total_seconds(),
+            });
+            if let Some(label) = &options.label {
+                event["label"] = json!(label);
+            }
+            println!("{}", event);
+        }
+        Format::PlainLine => {}
+    }
+}
+
+/// Prints when and how often the reminder would trigger.
+fn dry_run(options: &Options, target: DateTime<Local>) {
+    let repeat = options.repeat.as_secs();
+    match options.format {
+        Format::Text => {
+            println!("Would trigger at {}.", target.format("%Y-%m-%d %H:%M:%S"));
+            match (options.once, options.count, options.until) {
+                (true, _, _) => println!("Would remind once."),
+                (false, Some(count), Some(until)) => println!(
+                    "Would remind up to {} time(s), every {} second(s) until {}.",
+                    count,
+                    repeat,
+                    until.format("%Y-%m-%d %H:%M:%S")
+                ),
+                (false, Some(count), None) => println!(
+                    "Would remind {} time(s), every {} second(s).",
+                    count, repeat
+                ),
+                (false, None, Some(until)) => println!(
+                    "Would remind every {} second(s) until {}.",
+                    repeat,
+                    until.format("%Y-%m-%d %H:%M:%S")
+                ),
+                (false, None, None) => {
+                    println!("Would remind every {} second(s) until stopped.", repeat)
+                }
+            }
+        }
+        Format::Json => println!(
+            "{}",
+            json!({
+                "event": "dry_run",
+                "target": target.to_rfc3339(),
+                "count": if options.once { Some(1) } else { options.count },
+                "repeat": repeat,
+                "until": options.until.map(|until| until.to_rfc3339()),
+            })
+        ),
+        Format::PlainLine => println!("{}", target.to_rfc3339()),
+    }
+}
+
+fn run(args: Args, config: Config) -> io::Result<()> {
+    // Command line flags override the config file, which overrides the built-in defaults.
+    let args = config.apply(args);
+    // Listing only reads the saved reminders, without opening the log or checking the sounds.
+    if args.list {
+        return list(args.format);
+    }
+    let mut options = options(&args)?;
+    // There's no terminal to read keys from or count down on once detached.
+    if args.background {
+        options.snooze = None;
+    }
+
+    if args
+        .heartbeat
+        .as_ref()
+        .is_some_and(|every| every.total_seconds() == 0)
+    {
+        exit_with(Cli::command().error(
+            ErrorKind::ValueValidation,
+            "invalid value for '--heartbeat <DELTA>': Heartbeat interval must not be zero",
+        ));
+    }
+
+    if args.until_ack && !io::stdin().is_terminal() {
+        exit_with(Cli::command().error(
+            ErrorKind::InvalidValue,
+            "the argument '--until-ack' needs stdin to be a terminal",
+        ));
+    }
+
+    if args.tui && !io::stdout().is_terminal() {
+        exit_with(Cli::command().error(
+            ErrorKind::InvalidValue,
+            "the argument '--tui' needs stdout to be a terminal",
+        ));
+    }
+
+    if args.resume {
+        prepare_wait(args.background, options.format)?;
+        let start = Local::now();
+        let fired = with_heartbeat(&args.heartbeat, &args.heartbeat_file, || {
+            resume(&options, args.quiet)
+        })?;
+        if !args.quiet {
+            summary(fired, start, options.format);
+        }
+        return Ok(());
+    }
+
+    if let Some(path) = &args.schedule {
+        let default = default_message(config.message.as_deref());
+        // Read first, so an invalid file is reported before detaching.
+        let reminders = read_schedule(path, &options, &default, args.strict)?;
+        prepare_wait(args.background, options.format)?;
+        let start = Local::now();
+        let fired = with_heartbeat(&args.heartbeat, &args.heartbeat_file, || {
+            schedule(&reminders, args.quiet)
+        })?;
+        if !args.quiet {
+            summary(fired, start, options.format);
+        }
+        return Ok(());
+    }
 
-fn main() -> io::Result<()> {
-    let args = Args::parse();
+    let numeric = numeric_delta(args.hours, args.minutes, args.seconds).map(|delta| {
+        delta.unwrap_or_else(|e| {
+            exit_with(Cli::command().error(
+                ErrorKind::ValueValidation,
+                format!(
+                    "invalid value for '--hours', '--minutes' or '--seconds': {}",
+                    e
+                ),
+            ))
+        })
+    });
+    // With --at, --every or the numeric flags the only positional argument given is the message,
+    // unless it's a delta or there's another one after it.
+    let positional_delta = args.message.is_some()
+        || args
+            .delta
+            .as_deref()
+            .is_some_and(|delta| try_parse_delta(delta).is_ok());
+    if positional_delta && args.every.is_some() {
+        exit_with(Cli::command().error(
+            ErrorKind::ArgumentConflict,
+            "the argument '--every' cannot be used with '<DELTA>'",
+        ));
+    }
+    let mut queue = match (args.at, numeric.or(args.every.clone())) {
+        (Some(at), _) => {
+            if positional_delta {
+                exit_with(Cli::command().error(
+                    ErrorKind::ArgumentConflict,
+                    "the argument '--at' cannot be used with '<DELTA>'",
+                ));
+            }
+            vec![(Delta::until(at), args.delta)]
+        }
+        (None, Some(delta)) => {
+            if positional_delta {
+                exit_with(Cli::command().error(
+                    ErrorKind::ArgumentConflict,
+                    "the arguments '--hours', '--minutes' and '--seconds' cannot be used with '<DELTA>'",
+                ));
+            }
+            vec![(delta, args.delta)]
+        }
+        (None, None) => vec![(
+            parse_delta(&default_delta(args.delta.as_deref()).unwrap_or_else(|| {
+                exit_with(Cli::command().error(
+                    ErrorKind::MissingRequiredArgument,
+                    "the following required arguments were not provided:\n  <DELTA>",
+                ))
+            })),
+            args.message,
+        )],
+    };
+    for pair in args.more.chunks(2) {
+        queue.push((parse_delta(&pair[0]), pair.get(1).cloned()));
+    }
+    if let Some(first) = args.messages.first() {
+        queue[0].1 = Some(first.clone());
+    }
+    if let Some(path) = &args.message_file {
+        let message = fs::read_to_string(path).map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!("Could not read message file {}: {}", path.display(), e),
+            )
+        })?;
+        queue[0].1 = Some(message.trim_end().to_string());
+    }
+    // Time already elapsed is taken off every delta, leaving zero for those already due.
+    if let Some(since) = &args.since {
+        for (delta, _) in &mut queue {
+            *delta =
+                Delta::from_seconds(delta.total_seconds().saturating_sub(since.total_seconds()));
+        }
+    }
+    // Deltas counted from another time are turned into the time left from now.
+    if let Some(base) = args.relative_to {
+        for (delta, _) in &mut queue {
+            let target = delta.after(base).unwrap_or_else(|e| {
+                exit_with(Cli::command().error(
+                    ErrorKind::ValueValidation,
+                    format!("invalid value '{}' for '<DELTA>': {}", delta, e),
+                ))
+            });
+            let millis = (target - Local::now()).num_milliseconds().max(0);
+            *delta = Delta::from_seconds(millis.unsigned_abs().div_ceil(1000));
+        }
+    }
 
-    let seconds = u64::from(args.delta.hours) * 3600 + u64::from(args.delta.minutes) * 60;
-    println!(
-        "Remind in {} hour(s) and {} minute(s).",
-        args.delta.hours, args.delta.minutes
-    );
+    if let Some(jitter) = &args.jitter {
+        for (delta, _) in &mut queue {
+            *delta = Delta::from_seconds(remind::jitter(
+                delta.total_seconds(),
+                jitter.total_seconds(),
+            ));
+        }
+    }
 
-    thread::sleep(Duration::from_secs(seconds));
+    for (delta, _) in &queue {
+        tracing::debug!(%delta, seconds = delta.total_seconds(), "Parsed delta");
+        check_length(delta, args.strict, !args.dry_run)?;
+        if let Some(warn) = args
+            .warn_before
+            .as_ref()
+            .filter(|warn| warn.total_seconds() > delta.total_seconds())
+        {
+            exit_with(Cli::command().error(
+                ErrorKind::ValueValidation,
+                format!(
+                    "invalid value '{}' for '--warn-before <DELTA>': Longer than the {} until the reminder",
+                    warn, delta
+                ),
+            ));
+        }
+    }
 
-    print!("\x1b[2J\x1b[H");
-    io::stdout().flush()?;
+    // A message piped through stdin is used when none is given as an argument.
+    let piped = match queue.iter().any(|(_, message)| message.is_none()) {
+        true if !io::stdin().is_terminal() => io::read_to_string(io::stdin())?.trim().to_string(),
+        _ => String::new(),
+    };
+    let default = match piped.is_empty() {
+        true => default_message(config.message.as_deref()),
+        false => piped,
+    };
 
-    let message = args.message.unwrap_or("Time is up!".to_string());
-    loop {
-        println!("\x07{}", message);
-        if args.once {
-            break;
+    // Every delta is relative to now, each reminder repeating until the next one is due.
+    let start = Local::now();
+    let mut reminders: Vec<_> = queue
+        .into_iter()
+        .map(|(delta, message)| {
+            let mut reminder = Reminder::new(delta, message.as_deref().unwrap_or(&default));
+            reminder.options = options.clone();
+            reminder.options.set_at = Some(start);
+            let saved = Saved {
+                target: reminder
+                    .delta
+                    .after(start)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+                message: reminder.message.clone(),
+            };
+            Ok((reminder, saved))
+        })
+        .collect::<io::Result<_>>()?;
+    // Waited for one after the other, so the earliest has to come first whatever the order given.
+    reminders.sort_by_key(|(_, saved)| saved.target);
+    for i in 1..reminders.len() {
+        let next = reminders[i].1.target;
+        let until = &mut reminders[i - 1].0.options.until;
+        *until = Some(until.map_or(next, |until| until.min(next)));
+    }
+    if let Some(until) = options.until {
+        if let Some((_, saved)) = reminders.iter().find(|(_, saved)| saved.target >= until) {
+            exit_with(Cli::command().error(
+                ErrorKind::ValueValidation,
+                format!(
+                    "invalid value for '--repeat-until <HH:MM>': {} is before the reminder triggers at {}",
+                    until.format("%H:%M"),
+                    saved.target.format("%Y-%m-%d %H:%M:%S")
+                ),
+            ));
         }
-        thread::sleep(Duration::from_secs(30));
+    }
+
+    if !args.quiet {
+        for (reminder, _) in &reminders {
+            announce(&reminder.delta, &reminder.options);
+        }
+    }
+
+    if args.verbose {
+        eprintln!("Now: {}", start.format("%Y-%m-%d %H:%M:%S"));
+        for (reminder, saved) in &reminders {
+            eprintln!(
+                "Trigger at: {} ({} second(s))",
+                saved.target.format("%Y-%m-%d %H:%M:%S"),
+                reminder.delta.total_seconds()
+            );
+        }
+    }
+
+    if args.dry_run {
+        for (reminder, saved) in &reminders {
+            dry_run(&reminder.options, saved.target);
+        }
+        return Ok(());
+    }
+
+    prepare_wait(args.background, options.format)?;
+
+    if args.save {
+        for (_, saved) in &reminders {
+            storage::add(saved)?;
+        }
+    }
+
+    let (mut queue, saved): (Vec<_>, Vec<_>) = reminders
+        .into_iter()
+        .map(|(reminder, saved)| ((reminder, saved.target), saved))
+        .unzip();
+    let mut saved = saved.iter();
+    let mut paused = Duration::ZERO;
+    let fired = with_heartbeat(&args.heartbeat, &args.heartbeat_file, || {
+        remind::run_queue(&SystemClock, &mut queue, |reminder, mut target| {
+            let wait = |target: DateTime<Local>| match args.tui {
+                true => tui::countdown(target, &labelled(reminder)),
+                false => remind::wait_until(
+                    target,
+                    args.progress && !args.background && options.format == Format::Text,
+                    args.elapsed.then_some(start + paused),
+                ),
+            };
+            // The wait is split in two around the warning.
+            let mut more = Duration::ZERO;
+            if let Some(warn) = &args.warn_before {
+                let at = target - Duration::from_secs(warn.total_seconds());
+                if at > Local::now() {
+                    more += wait(at)?;
+                    target += more;
+                    warning(reminder, warn, &options);
+                }
+            }
+            more += wait(target)?;
+            paused += more;
+
+            if let Some(saved) = saved.next().filter(|_| args.save) {
+                storage::remove(saved)?;
+            }
+            Ok(more)
+        })
+    })?;
+
+    if !args.quiet {
+        summary(fired, start, options.format);
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn falls_back_to_env_defaults() {
+        env::remove_var("REMIND_DEFAULT_DELTA");
+        env::remove_var("REMIND_DEFAULT_MESSAGE");
+        assert_eq!(default_delta(None), None);
+        assert_eq!(default_message(None), "Time is up!");
+        assert_eq!(default_message(Some("From config")), "From config");
+
+        env::set_var("REMIND_DEFAULT_DELTA", "5m");
+        env::set_var("REMIND_DEFAULT_MESSAGE", "From env");
+        assert_eq!(default_delta(None).as_deref(), Some("5m"));
+        assert_eq!(default_delta(Some("2h")).as_deref(), Some("2h"));
+        assert_eq!(default_message(Some("From config")), "From env");
+
+        env::set_var("REMIND_DEFAULT_DELTA", " ");
+        assert_eq!(default_delta(None), None);
+
+        env::remove_var("REMIND_DEFAULT_DELTA");
+        env::remove_var("REMIND_DEFAULT_MESSAGE");
+    }
+
+    #[test]
+    fn adds_up_numeric_flags() {
+        assert_eq!(numeric_delta(None, None, None), None);
+        assert_eq!(
+            numeric_delta(None, None, Some(90)),
+            Some(Ok(Delta::from_seconds(90)))
+        );
+        assert_eq!(
+            numeric_delta(Some(2), Some(30), None),
+            Some(Ok(Delta::from_seconds(9000)))
+        );
+        assert_eq!(
+            numeric_delta(Some(1), Some(90), Some(30)),
+            Some(Ok(Delta::from_seconds(9030)))
+        );
+        assert_eq!(
+            numeric_delta(None, Some(0), None),
+            Some(Ok(Delta::from_seconds(0)))
+        );
+        assert_eq!(
+            numeric_delta(Some(u64::MAX), None, Some(1)).map(|delta| delta.map_err(|e| e.kind)),
+            Some(Err(DeltaErrorKind::Overflow))
+        );
+        assert_eq!(
+            numeric_delta(None, None, Some(u64::MAX)).map(|delta| delta.map_err(|e| e.kind)),
+            Some(Err(DeltaErrorKind::Overflow))
+        );
+    }
+}
@@ -0,0 +1,326 @@
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use chrono::{DateTime, Local};
+use notify_rust::Notification;
+use serde_json::json;
+
+use crate::{emit, emit_cleared, highlight, shell, sound, Clear, Delta, Format, Urgency, OUTPUT};
+
+/// A reminder as it triggers, handed to every `Notifier`.
+pub struct Fired<'a> {
+    /// Message with its placeholders filled in
+    pub message: &'a str,
+    /// Name shown in front of the message
+    pub label: Option<&'a str>,
+    /// How long ago the reminder was set, when that's shown
+    pub elapsed: Option<&'a Delta>,
+    /// When the reminder triggered
+    pub at: DateTime<Local>,
+    /// How many times the reminder fired before this one
+    pub count: u64,
+    /// Whether it's quiet hours, when only silent outputs should fire
+    pub quiet: bool,
+}
+
+impl Fired<'_> {
+    /// The message with the label in front of it, ex: [standup] Time is up!
+    pub fn labelled(&self) -> String {
+        match self.label {
+            Some(label) => format!("[{}] {}", label, self.message),
+            None => self.message.to_string(),
+        }
+    }
+}
+
+/// An output fired every time a reminder triggers, like the terminal or a log file.
+pub trait Notifier: Send + Sync {
+    fn fire(&self, fired: &Fired) -> io::Result<()>;
+}
+
+/// Terminal bell rung `count` times, three times as often for critical reminders.
+pub fn bell(count: u8, urgency: Urgency) -> String {
+    "\x07".repeat(match urgency {
+        Urgency::Critical => usize::from(count) * 3,
+        _ => usize::from(count),
+    })
+}
+
+/// Prints the reminder to stdout in the given format.
+pub struct Terminal {
+    format: Format,
+    clear: Clear,
+    color: bool,
+    bell: String,
+    repeat_bell: String,
+}
+
+impl Terminal {
+    /// Rings `bell` in front of the first message and `repeat_bell` in front of the repeats,
+    /// either left empty when a sound plays instead.
+    pub fn new(
+        format: Format,
+        clear: Clear,
+        color: bool,
+        bell: String,
+        repeat_bell: String,
+    ) -> Self {
+        Self {
+            format,
+            clear,
+            color,
+            bell,
+            repeat_bell,
+        }
+    }
+}
+
+impl Notifier for Terminal {
+    fn fire(&self, fired: &Fired) -> io::Result<()> {
+        match self.format {
+            Format::Text => {
+                let bell = match (fired.quiet, fired.count) {
+                    (true, _) => "",
+                    (false, 0) => &self.bell,
+                    (false, _) => &self.repeat_bell,
+                };
+                let shown = match fired.elapsed {
+                    Some(elapsed) => {
+                        format!("Reminder set {} ago: {}", elapsed, fired.labelled())
+                    }
+                    None => fired.labelled(),
+                };
+                let parts = [bell, &highlight(&shown, self.color), "\n"];
+                // Cleared for the first message only, so repeats pile up below it.
+                match fired.count {
+                    0 => emit_cleared(&OUTPUT, self.clear, &parts),
+                    _ => emit(&OUTPUT, &parts),
+                }
+            }
+            Format::Json => {
+                let mut event = json!({
+                    "event": "trigger",
+                    "timestamp": fired.at.to_rfc3339(),
+                    "message": fired.message,
+                });
+                if let Some(label) = fired.label {
+                    event["label"] = json!(label);
+                }
+                if let Some(elapsed) = fired.elapsed {
+                    event["elapsed"] = json!(elapsed.total_seconds());
+                }
+                emit(&OUTPUT, &[&event.to_string(), "\n"])
+            }
+            Format::PlainLine => {
+                let line = fired.labelled().replace(['\t', '\r', '\n'], " ");
+                emit(&OUTPUT, &[&fired.at.to_rfc3339(), "\t", &line, "\n"])
+            }
+        }
+    }
+}
+
+/// Appends every triggered reminder to a file, with a timestamp.
+pub struct Log {
+    file: Arc<File>,
+}
+
+impl Log {
+    pub fn new(file: File) -> Self {
+        Self {
+            file: Arc::new(file),
+        }
+    }
+}
+
+impl Notifier for Log {
+    fn fire(&self, fired: &Fired) -> io::Result<()> {
+        writeln!(
+            &*self.file,
+            "{} {}",
+            fired.at.to_rfc3339(),
+            fired.labelled()
+        )
+    }
+}
+
+/// Runs a shell command, warning when it fails without stopping the reminder.
+pub struct Exec {
+    command: String,
+    retries: u32,
+    delay: Duration,
+}
+
+impl Exec {
+    /// Runs the command again up to `retries` times after it fails, waiting `delay` in between.
+    pub fn new(command: &str, retries: u32, delay: Duration) -> Self {
+        Self {
+            command: command.to_string(),
+            retries,
+            delay,
+        }
+    }
+
+    /// Runs the command until it succeeds or runs out of retries, returning whether it succeeded.
+    fn run(&self) -> io::Result<bool> {
+        for retry in 0..=self.retries {
+            if retry > 0 {
+                tracing::debug!(retry, delay = ?self.delay, "Retrying failed command");
+                crate::sleep(self.delay)?;
+            }
+            let error = match shell(&self.command).status() {
+                Ok(status) if status.success() => return Ok(true),
+                Ok(status) => format!("Command {} failed: {}", self.command, status),
+                Err(e) => format!("Could not run {}: {}", self.command, e),
+            };
+            // Only the last failure is reported, the earlier ones being retried.
+            if retry == self.retries {
+                eprintln!("{}", error);
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+impl Notifier for Exec {
+    fn fire(&self, _fired: &Fired) -> io::Result<()> {
+        self.run().map(|_| ())
+    }
+}
+
+/// Shows a desktop notification, giving up after the first one that can't be shown.
+///
+/// Native toasts on Windows, D-Bus notifications on Linux and the BSDs and Notification Center
+/// on macOS.
+pub struct Desktop {
+    urgency: Urgency,
+    failed: AtomicBool,
+}
+
+impl Desktop {
+    /// The urgency is ignored where it isn't supported.
+    pub fn new(urgency: Urgency) -> Self {
+        Self {
+            urgency,
+            failed: AtomicBool::new(false),
+        }
+    }
+}
+
+impl Notifier for Desktop {
+    fn fire(&self, fired: &Fired) -> io::Result<()> {
+        if fired.quiet || self.failed.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let mut notification = Notification::new();
+        notification
+            .summary("glutools remind")
+            .body(&fired.labelled());
+        #[cfg(any(all(unix, not(target_os = "macos")), windows))]
+        notification.urgency(match self.urgency {
+            Urgency::Low => notify_rust::Urgency::Low,
+            Urgency::Normal => notify_rust::Urgency::Normal,
+            Urgency::Critical => notify_rust::Urgency::Critical,
+        });
+        if let Err(e) = notification.show() {
+            eprintln!("Could not show desktop notification: {}", e);
+            self.failed.store(true, Ordering::Relaxed);
+        }
+
+        Ok(())
+    }
+}
+
+/// Plays an audio file, falling back to the bell for good once a file can't be played.
+pub struct Sound {
+    first: Option<PathBuf>,
+    repeat: Option<PathBuf>,
+    bell: String,
+    failed: Mutex<Vec<PathBuf>>,
+}
+
+impl Sound {
+    /// Plays `first` on the first trigger and `repeat` on the repeats, ringing `bell` instead of
+    /// the ones that fail.
+    pub fn new(first: Option<PathBuf>, repeat: Option<PathBuf>, bell: String) -> Self {
+        Self {
+            first,
+            repeat,
+            bell,
+            failed: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl Notifier for Sound {
+    fn fire(&self, fired: &Fired) -> io::Result<()> {
+        let path = match fired.count {
+            0 => &self.first,
+            _ => &self.repeat,
+        };
+        let Some(path) = path.as_ref().filter(|_| !fired.quiet) else {
+            return Ok(());
+        };
+
+        // Not locked while playing, so reminders firing together don't wait for each other.
+        let failed = || self.failed.lock().unwrap_or_else(|e| e.into_inner());
+        if !failed().contains(path) {
+            match sound::play(path) {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    // Builds without the sound feature already warned about this at startup.
+                    if cfg!(feature = "sound") {
+                        eprintln!("Could not play {}: {}", path.display(), e);
+                    }
+                    failed().push(path.clone());
+                }
+            }
+        }
+
+        emit(&OUTPUT, &[&self.bell])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn retries_failed_commands() {
+        // Fails on the first two runs, counting them in a file, then succeeds.
+        let counter = env::temp_dir().join(format!("remind-exec-{}", process::id()));
+        let command = format!(
+            "n=$(cat {0} 2>/dev/null || echo 0); n=$((n + 1)); echo $n > {0}; [ $n -ge 3 ]",
+            counter.display()
+        );
+        let runs = || {
+            fs::read_to_string(&counter)
+                .unwrap()
+                .trim()
+                .parse::<u32>()
+                .unwrap()
+        };
+
+        assert!(!Exec::new(&command, 1, Duration::ZERO).run().unwrap());
+        assert_eq!(runs(), 2);
+        fs::remove_file(&counter).unwrap();
+
+        assert!(Exec::new(&command, 5, Duration::ZERO).run().unwrap());
+        assert_eq!(runs(), 3);
+        fs::remove_file(&counter).unwrap();
+
+        assert!(!Exec::new("exit 1", 0, Duration::ZERO).run().unwrap());
+    }
+}
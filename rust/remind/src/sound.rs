@@ -0,0 +1,35 @@
+use std::{error::Error, fs::File, path::Path};
+
+/// Plays an audio file on the default output device, blocking until it ends.
+#[cfg(feature = "sound")]
+pub fn play(path: &Path) -> Result<(), Box<dyn Error>> {
+    use std::io::BufReader;
+
+    let mut stream = rodio::OutputStreamBuilder::open_default_stream()?;
+    stream.log_on_drop(false);
+    let sink = rodio::play(stream.mixer(), BufReader::new(File::open(path)?))?;
+    sink.sleep_until_end();
+
+    Ok(())
+}
+
+#[cfg(not(feature = "sound"))]
+pub fn play(_path: &Path) -> Result<(), Box<dyn Error>> {
+    Err("remind was built without the sound feature, reinstall it with --features sound".into())
+}
+
+/// Checks that an audio file can be opened and decoded, without playing it.
+#[cfg(feature = "sound")]
+pub fn check(path: &Path) -> Result<(), Box<dyn Error>> {
+    rodio::Decoder::try_from(File::open(path)?)?;
+
+    Ok(())
+}
+
+/// Checks that an audio file can be opened, playing it falls back to the bell anyway.
+#[cfg(not(feature = "sound"))]
+pub fn check(path: &Path) -> Result<(), Box<dyn Error>> {
+    File::open(path)?;
+
+    Ok(())
+}
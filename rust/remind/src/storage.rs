@@ -0,0 +1,123 @@
+use std::{
+    fs,
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+    process,
+    sync::Mutex,
+};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+/// Held while the saved reminders are read, changed and written back, so reminders firing
+/// together don't undo each other's changes.
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// A reminder that has been scheduled but not fired yet.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Saved {
+    pub target: DateTime<Local>,
+    pub message: String,
+}
+
+/// Location of the saved reminders, ex: ~/.local/share/glutools/remind.json
+fn path() -> io::Result<PathBuf> {
+    let dir = dirs::data_dir()
+        .ok_or_else(|| io::Error::new(ErrorKind::NotFound, "No data directory found"))?;
+
+    Ok(dir.join("glutools").join("remind.json"))
+}
+
+/// Location of the do not disturb file when --dnd-file isn't given,
+/// ex: ~/.local/share/glutools/remind.dnd
+pub fn dnd_path() -> io::Result<PathBuf> {
+    Ok(path()?.with_extension("dnd"))
+}
+
+/// Reads all saved reminders, a missing file meaning there are none.
+pub fn load() -> io::Result<Vec<Saved>> {
+    load_from(&path()?)
+}
+
+fn load_from(path: &Path) -> io::Result<Vec<Saved>> {
+    match fs::read_to_string(path) {
+        Ok(contents) => serde_json::from_str(&contents).map_err(io::Error::other),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+fn store(path: &Path, reminders: &[Saved]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    // Written to a temporary file and renamed over, so it's never read half written.
+    let temporary = path.with_extension(format!("json.{}.tmp", process::id()));
+    fs::write(&temporary, serde_json::to_string_pretty(reminders)?)?;
+    fs::rename(temporary, path)
+}
+
+/// Reads the saved reminders, changes them with `f` and writes them back, holding LOCK throughout.
+fn update<T>(path: &Path, f: impl FnOnce(&mut Vec<Saved>) -> T) -> io::Result<T> {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut reminders = load_from(path)?;
+    let result = f(&mut reminders);
+    store(path, &reminders)?;
+
+    Ok(result)
+}
+
+pub fn add(reminder: &Saved) -> io::Result<()> {
+    update(&path()?, |reminders| reminders.push(reminder.clone()))
+}
+
+/// Removes all saved reminders, returning how many there were.
+pub fn clear() -> io::Result<usize> {
+    update(&path()?, |reminders| reminders.drain(..).count())
+}
+
+pub fn remove(reminder: &Saved) -> io::Result<()> {
+    update(&path()?, |reminders| reminders.retain(|r| r != reminder))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, thread};
+
+    use super::*;
+
+    #[test]
+    fn updates_from_many_threads() {
+        let dir = env::temp_dir().join(format!("remind-storage-{}", process::id()));
+        let path = dir.join("remind.json");
+        let saved: Vec<_> = (0..20)
+            .map(|n| Saved {
+                target: Local::now(),
+                message: format!("Reminder {}", n),
+            })
+            .collect();
+
+        // Overdue reminders all fire at once when resumed, each removing itself.
+        thread::scope(|scope| {
+            for reminder in &saved {
+                let path = &path;
+                scope.spawn(move || {
+                    update(path, |reminders| reminders.push(reminder.clone())).unwrap()
+                });
+            }
+        });
+        assert_eq!(load_from(&path).unwrap().len(), 20);
+        thread::scope(|scope| {
+            for reminder in &saved {
+                let path = &path;
+                scope.spawn(move || {
+                    update(path, |reminders| reminders.retain(|r| r != reminder)).unwrap()
+                });
+            }
+        });
+        assert!(load_from(&path).unwrap().is_empty());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
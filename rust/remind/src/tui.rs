@@ -0,0 +1,146 @@
+use std::{
+    io,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Flex, Layout},
+    style::{Modifier, Style},
+    text::{Line, Text},
+    widgets::Paragraph,
+    DefaultTerminal, Frame,
+};
+
+/// Big digits 0 to 9 followed by a colon, drawn five rows high.
+const GLYPHS: [[&str; 5]; 11] = [
+    ["███", "█ █", "█ █", "█ █", "███"],
+    ["  █", "  █", "  █", "  █", "  █"],
+    ["███", "  █", "███", "█  ", "███"],
+    ["███", "  █", "███", "  █", "███"],
+    ["█ █", "█ █", "███", "  █", "  █"],
+    ["███", "█  ", "███", "  █", "███"],
+    ["███", "█  ", "███", "█ █", "███"],
+    ["███", "  █", "  █", "  █", "  █"],
+    ["███", "█ █", "███", "█ █", "███"],
+    ["███", "█ █", "███", "  █", "███"],
+    [" ", "█", " ", "█", " "],
+];
+
+/// Counts down to `target` full screen, then flashes `message` until it's time to trigger.
+///
+/// Like `remind::wait_until`, pausing pushes `target` back and the time spent paused is returned.
+/// The bell is left to the reminder once it triggers.
+pub fn countdown(target: DateTime<Local>, message: &str) -> io::Result<Duration> {
+    // Restores the terminal on panics too, and raw mode turns Ctrl-C into a key press.
+    let mut terminal = ratatui::try_init()?;
+    let result = run(&mut terminal, target, message);
+    ratatui::try_restore()?;
+
+    result
+}
+
+fn run(
+    terminal: &mut DefaultTerminal,
+    mut target: DateTime<Local>,
+    message: &str,
+) -> io::Result<Duration> {
+    let mut paused = Duration::ZERO;
+    let mut tick = Instant::now();
+
+    loop {
+        if remind::is_cancelled() {
+            return Err(remind::cancelled());
+        }
+        if remind::is_paused() {
+            paused += tick.elapsed();
+            target += tick.elapsed();
+        }
+        tick = Instant::now();
+
+        let left = (target - Local::now()).to_std().unwrap_or_default();
+        if left.is_zero() {
+            break;
+        }
+        let seconds = remind::seconds_left(left);
+        let hints = match remind::is_paused() {
+            true => "Paused, press p to resume or q to quit",
+            false => "Press p to pause or q to quit",
+        };
+        terminal.draw(|frame| draw(frame, seconds, message, hints, false))?;
+
+        match key(Duration::from_millis(100))? {
+            Some('q') => return Err(remind::cancelled()),
+            Some('p') => remind::set_paused(!remind::is_paused()),
+            _ => {}
+        }
+    }
+
+    for flash in 0..6 {
+        terminal.draw(|frame| draw(frame, 0, message, "Time is up!", flash % 2 == 0))?;
+        remind::sleep(Duration::from_millis(250))?;
+    }
+
+    Ok(paused)
+}
+
+/// Draws the big countdown centered, with the message and key hints below it.
+fn draw(frame: &mut Frame, seconds: u64, message: &str, hints: &str, flash: bool) {
+    let clock = remind::hms(seconds);
+    let message_style = match flash {
+        true => Style::new().add_modifier(Modifier::BOLD | Modifier::REVERSED),
+        false => Style::new().add_modifier(Modifier::BOLD),
+    };
+    let message = Text::styled(message, message_style);
+
+    let [clock_area, message_area, hints_area] = Layout::vertical([
+        Constraint::Length(6),
+        Constraint::Length(message.height() as u16 + 1),
+        Constraint::Length(1),
+    ])
+    .flex(Flex::Center)
+    .areas(frame.area());
+
+    frame.render_widget(Paragraph::new(big(&clock)).centered(), clock_area);
+    frame.render_widget(Paragraph::new(message).centered(), message_area);
+    frame.render_widget(Paragraph::new(hints).centered(), hints_area);
+}
+
+/// Renders digits and colons with GLYPHS, one line per row.
+fn big(s: &str) -> Text<'static> {
+    let glyphs: Vec<_> = s
+        .chars()
+        .filter_map(|c| match c {
+            ':' => Some(&GLYPHS[10]),
+            _ => c.to_digit(10).map(|digit| &GLYPHS[digit as usize]),
+        })
+        .collect();
+
+    (0..5)
+        .map(|row| {
+            let line: Vec<_> = glyphs.iter().map(|glyph| glyph[row]).collect();
+            Line::from(line.join(" "))
+        })
+        .collect()
+}
+
+/// Waits up to `timeout` for a key press, mapping Ctrl-C to q.
+fn key(timeout: Duration) -> io::Result<Option<char>> {
+    if !event::poll(timeout)? {
+        return Ok(None);
+    }
+
+    match event::read()? {
+        Event::Key(KeyEvent {
+            code: KeyCode::Char(c),
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) => match c {
+            'c' if modifiers.contains(KeyModifiers::CONTROL) => Ok(Some('q')),
+            _ => Ok(Some(c)),
+        },
+        _ => Ok(None),
+    }
+}